@@ -0,0 +1,36 @@
+//! Compares building the same tree with SHA-256 and with the non-cryptographic
+//! `Fnv1aHasher` to show how much of the build time is spent hashing.
+//!
+//! Run with `cargo bench --features test-utils`.
+
+use std::time::{Duration, Instant};
+
+use merkle_trees::{Fnv1aHasher, MerkleHasher, MerkleTree, Sha256Hasher};
+
+const LEAVES: usize = 100_000;
+const ROUNDS: u32 = 5;
+
+fn time_build<H: MerkleHasher>(datas: &[Vec<u8>]) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let tree: MerkleTree<(), H> = MerkleTree::new(datas);
+        assert!(tree.root_hash().is_some());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let datas = (0..LEAVES)
+        .map(|i| i.to_le_bytes().to_vec())
+        .collect::<Vec<Vec<u8>>>();
+
+    let sha256 = time_build::<Sha256Hasher>(&datas);
+    let fnv1a = time_build::<Fnv1aHasher>(&datas);
+
+    println!("build {} leaves with sha256: {:?}", LEAVES, sha256);
+    println!("build {} leaves with fnv1a:  {:?}", LEAVES, fnv1a);
+    println!(
+        "hashing accounts for ~{:.0}% of the sha256 build",
+        100.0 * (1.0 - fnv1a.as_secs_f64() / sha256.as_secs_f64())
+    );
+}
@@ -0,0 +1,44 @@
+//! Compares `contains` on the same tree with and without a `SortedLeafIndex`.
+//!
+//! Run with `cargo bench --bench contains`.
+
+use std::time::{Duration, Instant};
+
+use merkle_trees::{MerkleTree, MerkleTreeBuilder};
+
+const LEAVES: usize = 100_000;
+const LOOKUPS: usize = 100;
+
+fn time_contains(tree: &MerkleTree, queries: &[Vec<u8>]) -> Duration {
+    let start = Instant::now();
+    for query in queries {
+        assert!(tree.contains(query));
+    }
+    start.elapsed() / queries.len() as u32
+}
+
+fn main() {
+    let datas = (0..LEAVES)
+        .map(|i| i.to_le_bytes().to_vec())
+        .collect::<Vec<Vec<u8>>>();
+    let queries = datas
+        .iter()
+        .step_by(LEAVES / LOOKUPS)
+        .cloned()
+        .collect::<Vec<Vec<u8>>>();
+
+    let plain: MerkleTree = MerkleTree::new(&datas);
+    let indexed: MerkleTree = MerkleTreeBuilder::new().sorted_index(true).build(&datas);
+
+    let linear = time_contains(&plain, &queries);
+    let sorted = time_contains(&indexed, &queries);
+
+    println!(
+        "contains over {} leaves without index: {:?}",
+        LEAVES, linear
+    );
+    println!(
+        "contains over {} leaves with index:    {:?}",
+        LEAVES, sorted
+    );
+}
@@ -0,0 +1,35 @@
+//! Compares auditing the same tree with `validate` and `validate_parallel`.
+//!
+//! Run with `cargo bench --features rayon`.
+
+use std::time::{Duration, Instant};
+
+use merkle_trees::MerkleTree;
+
+const LEAVES: usize = 100_000;
+const ROUNDS: u32 = 5;
+
+fn time_validate(validate: impl Fn() -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        assert!(validate());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let datas = (0..LEAVES)
+        .map(|i| i.to_le_bytes().to_vec())
+        .collect::<Vec<Vec<u8>>>();
+    let tree: MerkleTree = MerkleTree::new(&datas);
+
+    let serial = time_validate(|| tree.validate().is_ok());
+    let parallel = time_validate(|| tree.validate_parallel().is_ok());
+
+    println!("validate {} leaves serially:   {:?}", LEAVES, serial);
+    println!("validate {} leaves in parallel: {:?}", LEAVES, parallel);
+    println!(
+        "speedup: {:.1}x",
+        serial.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
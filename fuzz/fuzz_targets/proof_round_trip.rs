@@ -0,0 +1,33 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use merkle_trees::MerkleTree;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    leaves: Vec<Vec<u8>>,
+    index: usize,
+}
+
+// Every leaf of every tree must have a proof that verifies against the root.
+fuzz_target!(|input: Input| {
+    if input.leaves.is_empty() {
+        return;
+    }
+    let tree: MerkleTree = MerkleTree::new(&input.leaves);
+    let root = tree.root_hash().unwrap();
+    let index = input.index % input.leaves.len();
+
+    let proof = tree.generate_proof_by_index(index).unwrap();
+    assert_eq!(
+        MerkleTree::<()>::verify_proof(root, &input.leaves[index], &proof),
+        Ok(true)
+    );
+
+    let proof = tree.generate_proof(&input.leaves[index]).unwrap();
+    assert_eq!(
+        MerkleTree::<()>::verify_proof(root, &input.leaves[index], &proof),
+        Ok(true)
+    );
+});
@@ -0,0 +1,34 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use merkle_trees::MerkleTree;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    leaves: Vec<Vec<u8>>,
+    index: usize,
+    sibling: usize,
+    bit: usize,
+}
+
+// Flipping any bit of any sibling hash must make the proof fail.
+fuzz_target!(|input: Input| {
+    if input.leaves.len() < 2 {
+        return;
+    }
+    let tree: MerkleTree = MerkleTree::new(&input.leaves);
+    let root = tree.root_hash().unwrap();
+    let index = input.index % input.leaves.len();
+
+    let mut proof = tree.generate_proof_by_index(index).unwrap();
+    let position = input.sibling % proof.len();
+    let sibling = &mut proof.siblings[position];
+    let bit = input.bit % (sibling.hash.len() * 8);
+    sibling.hash[bit / 8] ^= 1 << (bit % 8);
+
+    assert_eq!(
+        MerkleTree::<()>::verify_proof(root, &input.leaves[index], &proof),
+        Ok(false)
+    );
+});
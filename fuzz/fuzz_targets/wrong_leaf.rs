@@ -0,0 +1,31 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use merkle_trees::MerkleTree;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    leaves: Vec<Vec<u8>>,
+    index: usize,
+    other: Vec<u8>,
+}
+
+// A proof for one leaf must not verify any other data, and data that is not a
+// leaf must not be reported as contained.
+fuzz_target!(|input: Input| {
+    if input.leaves.is_empty() || input.leaves.contains(&input.other) {
+        return;
+    }
+    let tree: MerkleTree = MerkleTree::new(&input.leaves);
+    let root = tree.root_hash().unwrap();
+    let index = input.index % input.leaves.len();
+
+    let proof = tree.generate_proof_by_index(index).unwrap();
+    assert_eq!(
+        MerkleTree::<()>::verify_proof(root, &input.other, &proof),
+        Ok(false)
+    );
+    assert!(tree.generate_proof(&input.other).is_none());
+    assert!(!tree.contains(&input.other));
+});
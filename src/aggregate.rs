@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use crate::{MerkleHasher, MerkleTree, Proof};
+
+/// Many inclusion proofs against one root, combined so every sibling hash is
+/// stored once. A sibling that another leaf's path already computes is not
+/// stored at all but referenced by its position on that path, which is where
+/// the savings over separate proofs come from for nearby leaves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregatedProof {
+    nodes: Vec<Vec<u8>>,
+    layout: Vec<Vec<Step>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Step {
+    is_left: bool,
+    sibling: Sibling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sibling {
+    /// An entry of `nodes`.
+    Node(usize),
+    /// The hash that path `item` reaches after `level` steps.
+    Path { item: usize, level: usize },
+}
+
+impl AggregatedProof {
+    /// Number of leaves the proof covers.
+    pub fn len(&self) -> usize {
+        self.layout.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layout.is_empty()
+    }
+
+    /// Encodes the proof with big-endian `u32` counts and indices: the node
+    /// count, each node as its length and bytes, the leaf count, then for
+    /// every leaf its step count and each step as a tag byte (bit 0 set if
+    /// the sibling is on the left, bit 1 set for a path reference) followed
+    /// by a node index, or by an item index and a level.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        put_u32(&mut bytes, self.nodes.len());
+        for node in &self.nodes {
+            put_u32(&mut bytes, node.len());
+            bytes.extend_from_slice(node);
+        }
+
+        put_u32(&mut bytes, self.layout.len());
+        for steps in &self.layout {
+            put_u32(&mut bytes, steps.len());
+            for step in steps {
+                let tag = u8::from(step.is_left);
+                match step.sibling {
+                    Sibling::Node(index) => {
+                        bytes.push(tag);
+                        put_u32(&mut bytes, index);
+                    }
+                    Sibling::Path { item, level } => {
+                        bytes.push(tag | 2);
+                        put_u32(&mut bytes, item);
+                        put_u32(&mut bytes, level);
+                    }
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Parses a proof produced by `to_bytes`. Returns `None` if the bytes are
+    /// malformed; whether the references make sense is left to
+    /// `verify_aggregated`.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<AggregatedProof> {
+        let mut nodes = Vec::new();
+        for _ in 0..take_u32(&mut bytes)? {
+            let len = take_u32(&mut bytes)?;
+            let (node, rest) = bytes.split_at_checked(len)?;
+            nodes.push(node.to_vec());
+            bytes = rest;
+        }
+
+        let mut layout = Vec::new();
+        for _ in 0..take_u32(&mut bytes)? {
+            let mut steps = Vec::new();
+            for _ in 0..take_u32(&mut bytes)? {
+                let (&tag, rest) = bytes.split_first()?;
+                bytes = rest;
+                let sibling = match tag & !1 {
+                    0 => Sibling::Node(take_u32(&mut bytes)?),
+                    2 => Sibling::Path {
+                        item: take_u32(&mut bytes)?,
+                        level: take_u32(&mut bytes)?,
+                    },
+                    _ => return None,
+                };
+                steps.push(Step {
+                    is_left: tag & 1 == 1,
+                    sibling,
+                });
+            }
+            layout.push(steps);
+        }
+
+        if !bytes.is_empty() {
+            return None;
+        }
+        Some(AggregatedProof { nodes, layout })
+    }
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: usize) {
+    let value = u32::try_from(value).expect("aggregated proof too large to encode");
+    bytes.extend_from_slice(&value.to_be_bytes());
+}
+
+fn take_u32(bytes: &mut &[u8]) -> Option<usize> {
+    let (value, rest) = bytes.split_first_chunk::<4>()?;
+    *bytes = rest;
+    usize::try_from(u32::from_be_bytes(*value)).ok()
+}
+
+impl<M: Clone, H: MerkleHasher> MerkleTree<M, H> {
+    /// Combines proofs for several `(data, proof)` pairs into one
+    /// `AggregatedProof`. Returns `None` if any proof does not verify against
+    /// `root`.
+    pub fn aggregate_proofs(root: &[u8], items: &[(Vec<u8>, Proof)]) -> Option<AggregatedProof> {
+        let mut paths = Vec::with_capacity(items.len());
+        for (data, proof) in items {
+            if Self::verify_proof(root, data, proof) != Ok(true) {
+                return None;
+            }
+            let mut hash = Self::hash_leaf(data);
+            let mut path = vec![hash.clone()];
+            for sibling in &proof.siblings {
+                hash = if sibling.is_left {
+                    Self::hash_nodes(&sibling.hash, &hash)
+                } else {
+                    Self::hash_nodes(&hash, &sibling.hash)
+                };
+                path.push(hash.clone());
+            }
+            paths.push(path);
+        }
+
+        // The lowest point at which any path computes each hash.
+        let mut computed: HashMap<&[u8], (usize, usize)> = HashMap::new();
+        for (item, path) in paths.iter().enumerate() {
+            for (level, hash) in path.iter().enumerate() {
+                let entry = computed.entry(hash).or_insert((item, level));
+                if level < entry.1 {
+                    *entry = (item, level);
+                }
+            }
+        }
+
+        let mut aggregated = AggregatedProof::default();
+        let mut stored: HashMap<&[u8], usize> = HashMap::new();
+        for (_, proof) in items {
+            let mut steps = Vec::with_capacity(proof.len());
+            for (level, node) in proof.siblings.iter().enumerate() {
+                let sibling = match computed.get(node.hash.as_slice()) {
+                    // Only hashes computed by this level are available while
+                    // verifying.
+                    Some(&(item, at)) if at <= level => Sibling::Path { item, level: at },
+                    _ => Sibling::Node(*stored.entry(&node.hash).or_insert_with(|| {
+                        aggregated.nodes.push(node.hash.clone());
+                        aggregated.nodes.len() - 1
+                    })),
+                };
+                steps.push(Step {
+                    is_left: node.is_left,
+                    sibling,
+                });
+            }
+            aggregated.layout.push(steps);
+        }
+        Some(aggregated)
+    }
+
+    /// Checks that every one of `leaves` is included under `root`, given an
+    /// aggregated proof over them in the same order. All paths are walked
+    /// together one level at a time, so each shared node is hashed once.
+    pub fn verify_aggregated(
+        root: &[u8],
+        leaves: &[Vec<u8>],
+        aggregated: &AggregatedProof,
+    ) -> bool {
+        if leaves.len() != aggregated.layout.len() {
+            return false;
+        }
+
+        let mut paths = leaves
+            .iter()
+            .map(|data| vec![Self::hash_leaf(data)])
+            .collect::<Vec<Vec<Vec<u8>>>>();
+        let height = aggregated.layout.iter().map(Vec::len).max().unwrap_or(0);
+        for level in 0..height {
+            for (item, steps) in aggregated.layout.iter().enumerate() {
+                let step = match steps.get(level) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                let sibling = match step.sibling {
+                    Sibling::Node(index) => aggregated.nodes.get(index),
+                    Sibling::Path { item, level: at } if at <= level => {
+                        paths.get(item).and_then(|path| path.get(at))
+                    }
+                    Sibling::Path { .. } => None,
+                };
+                let sibling = match sibling {
+                    Some(sibling) => sibling,
+                    None => return false,
+                };
+                let hash = &paths[item][level];
+                let parent = if step.is_left {
+                    Self::hash_nodes(sibling, hash)
+                } else {
+                    Self::hash_nodes(hash, sibling)
+                };
+                paths[item].push(parent);
+            }
+        }
+
+        paths
+            .iter()
+            .all(|path| path.last().is_some_and(|hash| hash == root))
+    }
+}
@@ -0,0 +1,80 @@
+use std::marker::PhantomData;
+
+use crate::{MerkleHasher, MerkleTree, Sha256Hasher};
+
+const BITS_PER_LEAF: usize = 10;
+const PROBES: u64 = 7;
+
+/// A Bloom filter over a tree's leaf hashes, built by
+/// `MerkleTree::build_leaf_bloom` and queried with
+/// `MerkleTree::maybe_contains`. It never reports a present leaf as absent,
+/// and with about ten bits per leaf reports roughly 1% of absent items as
+/// present, so it only ever rules items out; anything it lets through still
+/// needs the exact `MerkleTree::contains`.
+#[derive(Debug, Clone)]
+pub struct BloomFilter<H = Sha256Hasher> {
+    bits: Vec<u64>,
+    hasher: PhantomData<fn() -> H>,
+}
+
+impl<H: MerkleHasher> BloomFilter<H> {
+    fn with_leaf_count(leaf_count: usize) -> Self {
+        let words = (leaf_count * BITS_PER_LEAF).div_ceil(64).max(1);
+        BloomFilter {
+            bits: vec![0; words],
+            hasher: PhantomData,
+        }
+    }
+
+    fn insert_hash(&mut self, leaf_hash: &[u8]) {
+        for bit in self.probes(leaf_hash) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// `false` if `leaf_hash` is certainly not a leaf hash of the tree the
+    /// filter was built from, `true` if it may be.
+    pub fn maybe_contains_hash(&self, leaf_hash: &[u8]) -> bool {
+        self.probes(leaf_hash)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Bit positions for `leaf_hash`, by double hashing two FNV-1a digests.
+    fn probes(&self, leaf_hash: &[u8]) -> impl Iterator<Item = usize> {
+        let len = self.bits.len() as u64 * 64;
+        let (first, second) = (fnv1a(leaf_hash, 0), fnv1a(leaf_hash, 1) | 1);
+        (0..PROBES).map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % len) as usize)
+    }
+}
+
+fn fnv1a(bytes: &[u8], seed: u8) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in std::iter::once(&seed).chain(bytes) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+impl<M: Clone, H: MerkleHasher> MerkleTree<M, H> {
+    /// Builds a Bloom filter over the tree's leaf hashes, to cheaply discard
+    /// obviously absent items before an exact `contains`.
+    pub fn build_leaf_bloom(&self) -> BloomFilter<H> {
+        let leaves = self.leaf_nodes();
+        let mut bloom = BloomFilter::with_leaf_count(leaves.len());
+        for leaf in leaves {
+            bloom.insert_hash(&leaf.hash);
+        }
+        bloom
+    }
+
+    /// `false` if `data` is certainly not a leaf of this tree, `true` if it
+    /// may be. `bloom` must come from this tree's `build_leaf_bloom`; `data`
+    /// is hashed the way `contains` hashes it, so normalization and empty
+    /// leaf sentinels apply. Trees built with `MerkleTreeBuilder::bind_index`
+    /// hash leaves by position, which the data alone does not give, so for
+    /// them this always returns `true`.
+    pub fn maybe_contains(&self, bloom: &BloomFilter<H>, data: &[u8]) -> bool {
+        self.bind_index || bloom.maybe_contains_hash(&self.leaf_data_hash(0, data))
+    }
+}
@@ -0,0 +1,126 @@
+use std::marker::PhantomData;
+
+use crate::{BuildError, MerkleHasher, MerkleTree, Sha256Hasher};
+
+/// Canonicalizes leaf data before it is hashed; see
+/// `MerkleTreeBuilder::normalize`.
+pub type Normalizer = fn(&[u8]) -> Vec<u8>;
+
+/// What to do with leaves whose data is empty, which otherwise hash like any
+/// other data and may be confused with "no leaf".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyLeaves {
+    /// Hash empty data like any other.
+    #[default]
+    Hash,
+    /// Refuse to build with `BuildError::EmptyLeaf`, and later refuse empty
+    /// data in `append`, `insert` and `update`, which then return `false`.
+    Reject,
+    /// Give every empty leaf the all-zero hash of `MerkleHasher::hash_len`
+    /// bytes, which no real data is expected to hash to. This also applies
+    /// to empty data passed to `contains`, `append` and the like later.
+    Sentinel,
+}
+
+/// Configures how a `MerkleTree` hashes its leaves before building it.
+#[derive(Debug, Clone, Copy)]
+pub struct MerkleTreeBuilder<M = (), H = Sha256Hasher> {
+    bind_index: bool,
+    normalize: Option<Normalizer>,
+    empty_leaves: EmptyLeaves,
+    sorted_index: bool,
+    tree: PhantomData<fn() -> MerkleTree<M, H>>,
+}
+
+impl<M, H> Default for MerkleTreeBuilder<M, H> {
+    fn default() -> Self {
+        MerkleTreeBuilder {
+            bind_index: false,
+            normalize: None,
+            empty_leaves: EmptyLeaves::Hash,
+            sorted_index: false,
+            tree: PhantomData,
+        }
+    }
+}
+
+impl<M: Clone, H: MerkleHasher> MerkleTreeBuilder<M, H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hashes every leaf together with its index, see
+    /// `MerkleTree::hash_leaf_at`, so a proof only verifies at the position it
+    /// was issued for and must be checked with `MerkleTree::verify_proof_at`.
+    /// Leaves added later with `append`, `insert` or `update` are bound to
+    /// the index they land at. Bound hashes cannot be moved without the leaf
+    /// data, so `insert` and `remove_at` refuse to shift existing leaves.
+    pub fn bind_index(mut self, bind_index: bool) -> Self {
+        self.bind_index = bind_index;
+        self
+    }
+
+    /// Canonicalizes leaf data with `normalize` before it is hashed, both
+    /// when building and in every later `contains`, `generate_proof`,
+    /// `append`, `insert` and `update`, so e.g. a case-folding function makes
+    /// membership case-insensitive. The static verifiers see only the data
+    /// they are given, so callers must normalize it the same way first.
+    pub fn normalize(mut self, normalize: Normalizer) -> Self {
+        self.normalize = Some(normalize);
+        self
+    }
+
+    /// Chooses how empty leaf data is handled; see `EmptyLeaves`.
+    pub fn empty_leaves(mut self, empty_leaves: EmptyLeaves) -> Self {
+        self.empty_leaves = empty_leaves;
+        self
+    }
+
+    /// Maintains a `SortedLeafIndex` for fast lookups; see
+    /// `MerkleTree::enable_sorted_index`.
+    pub fn sorted_index(mut self, sorted_index: bool) -> Self {
+        self.sorted_index = sorted_index;
+        self
+    }
+
+    /// Builds the tree over `datas` with the layout of `MerkleTree::new`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `try_build` would fail, which only happens with
+    /// `EmptyLeaves::Reject`.
+    pub fn build(self, datas: &[Vec<u8>]) -> MerkleTree<M, H> {
+        self.try_build(datas)
+            .unwrap_or_else(|err| panic!("cannot build tree: {}", err))
+    }
+
+    /// Like `build`, but reports leaves the configuration rejects.
+    pub fn try_build(self, datas: &[Vec<u8>]) -> Result<MerkleTree<M, H>, BuildError> {
+        if self.empty_leaves == EmptyLeaves::Reject {
+            if let Some(index) = datas.iter().position(|data| data.is_empty()) {
+                return Err(BuildError::EmptyLeaf { index });
+            }
+        }
+
+        let mut template = MerkleTree::new(&[]);
+        self.configure(&mut template);
+        let leaf_hashes = datas
+            .iter()
+            .enumerate()
+            .map(|(index, data)| template.leaf_data_hash(index, data))
+            .collect::<Vec<Vec<u8>>>();
+
+        let mut tree = MerkleTree::from_leaf_hashes(&leaf_hashes);
+        self.configure(&mut tree);
+        Ok(tree)
+    }
+
+    fn configure(&self, tree: &mut MerkleTree<M, H>) {
+        tree.bind_index = self.bind_index;
+        tree.normalize = self.normalize;
+        tree.empty_leaves = self.empty_leaves;
+        if self.sorted_index {
+            tree.enable_sorted_index();
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why `verify_proof_detailed` rejected a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The proof recombined to a different root than the one given.
+    RootMismatch,
+    /// The proof has no siblings but the leaf is not the root, i.e. the tree
+    /// has more than one leaf.
+    EmptyProof,
+    /// The root, or with `position` set the sibling hash at that position
+    /// (counting from the leaf), is `found` bytes long while the hasher
+    /// produces `expected` bytes, e.g. a SHA-256 root handed to a SHA-512
+    /// verifier.
+    HashLengthMismatch {
+        position: Option<usize>,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::RootMismatch => write!(f, "proof does not lead to the expected root"),
+            VerifyError::EmptyProof => write!(f, "proof is empty but the leaf is not the root"),
+            VerifyError::HashLengthMismatch {
+                position: None,
+                expected,
+                found,
+            } => write!(f, "root is {} bytes long, expected {}", found, expected),
+            VerifyError::HashLengthMismatch {
+                position: Some(position),
+                expected,
+                found,
+            } => write!(
+                f,
+                "sibling {} is {} bytes long, expected {}",
+                position, found, expected
+            ),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+/// Why `MerkleTreeBuilder::try_build` refused to build a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// The leaf at `index` has no data and empty leaves are rejected.
+    EmptyLeaf { index: usize },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::EmptyLeaf { index } => write!(f, "leaf {} is empty", index),
+        }
+    }
+}
+
+impl Error for BuildError {}
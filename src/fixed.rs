@@ -0,0 +1,91 @@
+use crate::{MerkleHasher, Sha256Hasher};
+
+/// A Merkle tree over a compile-time number of leaves `N`, which must be a
+/// power of two. Nodes are stored flattened in heap order (node `i` has
+/// children `2i` and `2i + 1`, the root is node 1 and the leaves are nodes
+/// `N..2N`), so there is no `Option` or boxing overhead and every proof has
+/// exactly `DEPTH` siblings.
+///
+/// For the same leaves the root equals the one built by `MerkleTree::new`.
+#[derive(Debug, Clone)]
+pub struct FixedMerkleTree<const N: usize> {
+    internal: [Vec<u8>; N],
+    leaves: [Vec<u8>; N],
+}
+
+impl<const N: usize> FixedMerkleTree<N> {
+    const POWER_OF_TWO: () = assert!(
+        N.is_power_of_two(),
+        "FixedMerkleTree requires a power-of-two leaf count"
+    );
+
+    /// Number of siblings in every proof, i.e. `log2(N)`.
+    pub const DEPTH: usize = N.trailing_zeros() as usize;
+
+    pub fn new(datas: &[Vec<u8>; N]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::POWER_OF_TWO;
+
+        let leaves = std::array::from_fn(|i| Sha256Hasher::hash_leaf(&datas[i]));
+        let mut tree = FixedMerkleTree {
+            internal: std::array::from_fn(|_| Vec::new()),
+            leaves,
+        };
+        for i in (1..N).rev() {
+            let hash = Sha256Hasher::hash_nodes(tree.node(2 * i), tree.node(2 * i + 1));
+            tree.internal[i] = hash;
+        }
+        tree
+    }
+
+    pub fn root_hash(&self) -> &Vec<u8> {
+        self.node(1)
+    }
+
+    pub fn leaf_hash(&self, index: usize) -> Option<&Vec<u8>> {
+        self.leaves.get(index)
+    }
+
+    /// Returns the sibling hashes from the leaf at `index` up to the root.
+    /// The returned proof always has `DEPTH` entries.
+    pub fn proof(&self, index: usize) -> Option<Vec<Vec<u8>>> {
+        if index >= N {
+            return None;
+        }
+
+        let mut position = N + index;
+        let mut proof = Vec::with_capacity(Self::DEPTH);
+        while position > 1 {
+            proof.push(self.node(position ^ 1).clone());
+            position /= 2;
+        }
+        Some(proof)
+    }
+
+    /// Checks a proof produced by `proof` for `data` at `index` against `root`.
+    pub fn verify_proof(root: &[u8], data: &[u8], index: usize, proof: &[Vec<u8>]) -> bool {
+        if index >= N || proof.len() != Self::DEPTH {
+            return false;
+        }
+
+        let mut position = N + index;
+        let mut hash = Sha256Hasher::hash_leaf(data);
+        for sibling in proof {
+            hash = if position.is_multiple_of(2) {
+                Sha256Hasher::hash_nodes(&hash, sibling)
+            } else {
+                Sha256Hasher::hash_nodes(sibling, &hash)
+            };
+            position /= 2;
+        }
+        hash == root
+    }
+
+    fn node(&self, position: usize) -> &Vec<u8> {
+        if position >= N {
+            &self.leaves[position - N]
+        } else {
+            &self.internal[position]
+        }
+    }
+}
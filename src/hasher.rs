@@ -0,0 +1,194 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use crypto::digest::Digest;
+use crypto::sha2::{Sha256, Sha512};
+
+/// The hash function a tree uses for its leaves and internal nodes.
+pub trait MerkleHasher {
+    /// Hashes the concatenation of `datas`.
+    fn hash(datas: &[&[u8]]) -> Vec<u8>;
+
+    /// Length in bytes of every hash this hasher produces.
+    fn hash_len() -> usize;
+
+    fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        Self::hash(&[data])
+    }
+
+    fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+        Self::hash(&[left, right])
+    }
+}
+
+/// SHA-256, storing each digest as its lowercase hex string bytes. This is the
+/// default hasher and applies no domain separation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256Hasher;
+
+impl MerkleHasher for Sha256Hasher {
+    fn hash(datas: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        for data in datas.iter() {
+            hasher.input(data)
+        }
+        let digest = hasher.result_str().as_bytes().to_vec();
+        #[cfg(feature = "trace")]
+        crate::record_hash(datas, &digest);
+        digest
+    }
+
+    fn hash_len() -> usize {
+        64
+    }
+}
+
+/// SHA-256, storing the raw 32-byte digest. Wrapped in `DomainSeparated` it
+/// is exactly the RFC 6962 / RFC 9162 tree hash that Certificate
+/// Transparency logs use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256RawHasher;
+
+impl MerkleHasher for Sha256RawHasher {
+    fn hash(datas: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        for data in datas.iter() {
+            hasher.input(data)
+        }
+        let mut digest = vec![0; hasher.output_bytes()];
+        hasher.result(&mut digest);
+        #[cfg(feature = "trace")]
+        crate::record_hash(datas, &digest);
+        digest
+    }
+
+    fn hash_len() -> usize {
+        32
+    }
+}
+
+/// SHA-512, storing the raw 64-byte digest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha512Hasher;
+
+impl MerkleHasher for Sha512Hasher {
+    fn hash(datas: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        for data in datas.iter() {
+            hasher.input(data)
+        }
+        let mut digest = vec![0; hasher.output_bytes()];
+        hasher.result(&mut digest);
+        #[cfg(feature = "trace")]
+        crate::record_hash(datas, &digest);
+        digest
+    }
+
+    fn hash_len() -> usize {
+        64
+    }
+}
+
+/// Wraps a hasher with RFC 6962 style domain separation: leaves are hashed as
+/// `H(0x00 || data)` and internal nodes as `H(0x01 || left || right)`.
+///
+/// Without separation a leaf and an internal node are hashed the same way, so
+/// the concatenation of two child hashes is itself valid "leaf data" whose
+/// hash equals their parent. An attacker can then present an internal node as
+/// a leaf (a second-preimage attack), `contains` will report it as present,
+/// and a tree made of identical leaves has siblings equal to the leaf itself,
+/// which makes proofs easy to misread. Trees that accept untrusted leaves or
+/// proofs should use `DomainSeparated<Sha256Hasher>`, or
+/// `DomainSeparated<Sha256RawHasher>` where hashes must match RFC 6962.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DomainSeparated<H>(PhantomData<H>);
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+impl<H: MerkleHasher> MerkleHasher for DomainSeparated<H> {
+    fn hash(datas: &[&[u8]]) -> Vec<u8> {
+        H::hash(datas)
+    }
+
+    fn hash_len() -> usize {
+        H::hash_len()
+    }
+
+    fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        H::hash(&[&[LEAF_PREFIX], data])
+    }
+
+    fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+        H::hash(&[&[NODE_PREFIX], left, right])
+    }
+}
+
+thread_local! {
+    static HASH_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Forwards every call to `H`, counting them on the current thread; see
+/// `MerkleTree::new_counting_hashes`.
+pub(crate) struct Counting<H>(PhantomData<H>);
+
+impl<H> Counting<H> {
+    /// Hash calls made through any `Counting` hasher on this thread so far.
+    pub(crate) fn calls() -> usize {
+        HASH_CALLS.with(Cell::get)
+    }
+
+    fn count() {
+        HASH_CALLS.with(|calls| calls.set(calls.get() + 1));
+    }
+}
+
+impl<H: MerkleHasher> MerkleHasher for Counting<H> {
+    fn hash(datas: &[&[u8]]) -> Vec<u8> {
+        Self::count();
+        H::hash(datas)
+    }
+
+    fn hash_len() -> usize {
+        H::hash_len()
+    }
+
+    fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        Self::count();
+        H::hash_leaf(data)
+    }
+
+    fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+        Self::count();
+        H::hash_nodes(left, right)
+    }
+}
+
+/// 64-bit FNV-1a. It is fast and **not** collision resistant, so it must never
+/// be used for production trees; it exists so tests, fuzzing and benches can
+/// build large trees cheaply.
+#[cfg(feature = "test-utils")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fnv1aHasher;
+
+#[cfg(feature = "test-utils")]
+impl MerkleHasher for Fnv1aHasher {
+    fn hash(datas: &[&[u8]]) -> Vec<u8> {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut hash = OFFSET_BASIS;
+        for byte in datas.iter().flat_map(|data| data.iter()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+        let digest = hash.to_be_bytes().to_vec();
+        #[cfg(feature = "trace")]
+        crate::record_hash(datas, &digest);
+        digest
+    }
+
+    fn hash_len() -> usize {
+        8
+    }
+}
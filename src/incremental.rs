@@ -0,0 +1,157 @@
+use std::marker::PhantomData;
+
+use crate::{MerkleHasher, Sha256Hasher};
+
+/// Appends `leaf_hash` to an incremental Merkle frontier and returns the new
+/// root, hashing with `Sha256Hasher`; `IncrementalMerkleTree` takes any
+/// `MerkleHasher`.
+///
+/// `frontier[i]` holds the root of a complete subtree of `2^i` leaves, or
+/// `None` if there is no such subtree pending. Appending works like a binary
+/// counter: the new leaf is carried up, merging with every occupied level
+/// until it lands in an empty slot. The root is then folded from the smallest
+/// subtree upward, so only `O(log n)` hashes are kept.
+///
+/// For power-of-two leaf counts the root matches `compute_root` over the same
+/// leaves. For other counts the left subtree is always the largest complete
+/// one (the append-only layout), which differs from the midpoint split used
+/// by `MerkleTree::new`.
+pub fn append_to_frontier(frontier: &mut Vec<Option<Vec<u8>>>, leaf_hash: Vec<u8>) -> Vec<u8> {
+    push_to_frontier::<Sha256Hasher>(frontier, leaf_hash);
+    frontier_root::<Sha256Hasher>(frontier).unwrap()
+}
+
+/// Carries `leaf_hash` into `frontier` without folding the root, which costs
+/// one hash per occupied level it merges with.
+fn push_to_frontier<H: MerkleHasher>(frontier: &mut Vec<Option<Vec<u8>>>, leaf_hash: Vec<u8>) {
+    let mut carry = leaf_hash;
+    let mut level = 0;
+    loop {
+        if level == frontier.len() {
+            frontier.push(None);
+        }
+        match frontier[level].take() {
+            Some(left) => {
+                carry = H::hash_nodes(&left, &carry);
+                level += 1;
+            }
+            None => {
+                frontier[level] = Some(carry);
+                break;
+            }
+        }
+    }
+}
+
+/// Folds a stream of leaf hashes into a root, keeping only `O(log n)` hashes
+/// in memory. Leaves are carried into a frontier as by `append_to_frontier`,
+/// but the root is folded only once the stream ends, so `n` leaves cost
+/// `n - 1` node hashes. Returns `None` for an empty stream. Hashes with
+/// `Sha256Hasher`, like `append_to_frontier`.
+///
+/// The result uses the append-only layout, so it equals `compute_root` over
+/// the same leaves for power-of-two counts and `MerkleTree::append` for all
+/// counts.
+pub fn compute_root_streaming<I: Iterator<Item = Vec<u8>>>(leaf_hashes: I) -> Option<Vec<u8>> {
+    let mut frontier = Vec::new();
+    for leaf_hash in leaf_hashes {
+        push_to_frontier::<Sha256Hasher>(&mut frontier, leaf_hash);
+    }
+    frontier_root::<Sha256Hasher>(&frontier)
+}
+
+fn frontier_root<H: MerkleHasher>(frontier: &[Option<Vec<u8>>]) -> Option<Vec<u8>> {
+    let mut root: Option<Vec<u8>> = None;
+    for subtree in frontier.iter().flatten() {
+        root = match root {
+            None => Some(subtree.clone()),
+            Some(right) => Some(H::hash_nodes(subtree, &right)),
+        };
+    }
+    root
+}
+
+/// An append-only Merkle tree that keeps only its frontier, `O(log n)`
+/// hashes, instead of every node, hashing with `H`. Roots match
+/// `MerkleTree<M, H>::append` over the same leaves, and
+/// `compute_root_streaming` for the default `Sha256Hasher`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalMerkleTree<H = Sha256Hasher> {
+    frontier: Vec<Option<Vec<u8>>>,
+    leaf_count: usize,
+    hasher: PhantomData<fn() -> H>,
+}
+
+impl<H> Default for IncrementalMerkleTree<H> {
+    fn default() -> Self {
+        IncrementalMerkleTree {
+            frontier: Vec::new(),
+            leaf_count: 0,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H: MerkleHasher> IncrementalMerkleTree<H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty tree expecting about `n` appends, with the frontier sized for
+    /// that many leaves so it does not grow while they are appended.
+    pub fn with_capacity(n: usize) -> Self {
+        let mut tree = Self::new();
+        tree.reserve(n);
+        tree
+    }
+
+    /// Reserves frontier levels for at least `additional` more appends.
+    pub fn reserve(&mut self, additional: usize) {
+        let leaf_count = self.leaf_count.saturating_add(additional);
+        let levels = (usize::BITS - leaf_count.leading_zeros()) as usize;
+        self.frontier
+            .reserve(levels.saturating_sub(self.frontier.len()));
+    }
+
+    /// Resumes a tree from a frontier saved with `export_frontier` and the
+    /// number of leaves it had. Returns `None` if the frontier does not fit
+    /// that leaf count: level `i` must be occupied exactly when bit `i` of
+    /// `leaf_count` is set.
+    pub fn from_frontier(frontier: Vec<Option<Vec<u8>>>, leaf_count: usize) -> Option<Self> {
+        let fits_count = frontier.iter().enumerate().all(|(level, subtree)| {
+            subtree.is_some() == (leaf_count.checked_shr(level as u32).unwrap_or(0) & 1 == 1)
+        });
+        let covers_count = leaf_count.checked_shr(frontier.len() as u32).unwrap_or(0) == 0;
+        (fits_count && covers_count).then_some(IncrementalMerkleTree {
+            frontier,
+            leaf_count,
+            hasher: PhantomData,
+        })
+    }
+
+    /// The frontier, for persisting and later `from_frontier`.
+    pub fn export_frontier(&self) -> Vec<Option<Vec<u8>>> {
+        self.frontier.clone()
+    }
+
+    /// Hashes `data` as a leaf and appends it, returning the new root.
+    pub fn append(&mut self, data: &[u8]) -> Vec<u8> {
+        self.append_hash(H::hash_leaf(data))
+    }
+
+    /// Appends an already computed leaf hash, returning the new root.
+    pub fn append_hash(&mut self, leaf_hash: Vec<u8>) -> Vec<u8> {
+        self.leaf_count += 1;
+        push_to_frontier::<H>(&mut self.frontier, leaf_hash);
+        frontier_root::<H>(&self.frontier).unwrap()
+    }
+
+    /// The current root, or `None` before the first append.
+    pub fn root(&self) -> Option<Vec<u8>> {
+        frontier_root::<H>(&self.frontier)
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+}
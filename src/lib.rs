@@ -0,0 +1,1652 @@
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+mod aggregate;
+mod bloom;
+mod builder;
+mod error;
+mod fixed;
+mod hasher;
+mod incremental;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod proof;
+mod pruned;
+mod resolver;
+mod sorted;
+#[cfg(feature = "tokio")]
+mod stream;
+#[cfg(feature = "trace")]
+mod trace;
+
+pub use aggregate::AggregatedProof;
+pub use bloom::BloomFilter;
+pub use builder::{EmptyLeaves, MerkleTreeBuilder, Normalizer};
+pub use error::{BuildError, VerifyError};
+pub use fixed::FixedMerkleTree;
+#[cfg(feature = "test-utils")]
+pub use hasher::Fnv1aHasher;
+pub use hasher::{DomainSeparated, MerkleHasher, Sha256Hasher, Sha256RawHasher, Sha512Hasher};
+pub use incremental::{append_to_frontier, compute_root_streaming, IncrementalMerkleTree};
+pub use proof::{Proof, ProofNode};
+pub use pruned::PrunedTree;
+pub use resolver::LeafResolver;
+pub use sorted::SortedLeafIndex;
+#[cfg(feature = "trace")]
+pub use trace::{record_hash, TraceRecord};
+
+use hasher::Counting;
+
+/// Computes the root `MerkleTree::new` would produce over `datas` without
+/// building any nodes. Returns `None` for no leaves.
+pub fn compute_root(datas: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let leaf_hashes = datas
+        .iter()
+        .map(|data| Sha256Hasher::hash_leaf(data))
+        .collect::<Vec<Vec<u8>>>();
+    root_of_leaf_hashes::<Sha256Hasher>(&leaf_hashes)
+}
+
+fn root_of_leaf_hashes<H: MerkleHasher>(leaf_hashes: &[Vec<u8>]) -> Option<Vec<u8>> {
+    match leaf_hashes.len() {
+        0 => None,
+        1 => Some(leaf_hashes[0].clone()),
+        len => {
+            let mid = len / 2;
+            let left = root_of_leaf_hashes::<H>(&leaf_hashes[..mid])?;
+            let right = root_of_leaf_hashes::<H>(&leaf_hashes[mid..])?;
+            Some(H::hash_nodes(&left, &right))
+        }
+    }
+}
+
+/// How a tree is built from its leaves. Both strategies agree whenever the
+/// leaf count is a power of two and differ otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuildStrategy {
+    /// Recursive and top-down: each node gives its left child the first half
+    /// of its leaves, rounded down. Five leaves split as `2 | 3`. This is the
+    /// layout `new` has always built.
+    #[default]
+    TopDown,
+    /// Iterative and bottom-up: adjacent nodes are paired level by level and
+    /// an odd node at the end of a level is carried up unchanged. Five leaves
+    /// split as `4 | 1`. This is the canonical pairing most Merkle libraries
+    /// use and matches the append-only layout of `append`.
+    BottomUp,
+}
+
+/// How other Merkle tree conventions treat a level with an odd number of
+/// nodes, for `MerkleTree::root_under_policy`. All policies give the same
+/// root when the leaf count is a power of two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OddLeafPolicy {
+    /// Split every node's leaves at the midpoint, as `MerkleTree::new` does.
+    Midpoint,
+    /// Pair bottom-up and carry an odd last node up unchanged, as
+    /// `BuildStrategy::BottomUp` and RFC 6962 do.
+    Promote,
+    /// Pair bottom-up and pair an odd last node with itself, as Bitcoin does.
+    Duplicate,
+}
+
+/// A Merkle tree whose leaves may carry an application-defined metadata tag
+/// of type `M`. Metadata travels with the leaf but never affects hashing.
+/// Leaves and internal nodes are hashed with `H`.
+///
+/// All read-only queries (`root_hash`, `generate_proof`, `verify_proof`, ...)
+/// take `&self` and the tree holds no interior mutability, so it is
+/// `Send + Sync` whenever `M` is and a built tree can be shared between
+/// threads in an `Arc` without locking.
+#[derive(Debug)]
+pub struct MerkleTree<M = (), H = Sha256Hasher> {
+    root: Option<Box<MerkleNode<M>>>,
+    historical_roots: Vec<Vec<u8>>,
+    append_only: bool,
+    bind_index: bool,
+    normalize: Option<Normalizer>,
+    empty_leaves: EmptyLeaves,
+    sorted_index: Option<SortedLeafIndex>,
+    hasher: PhantomData<fn() -> H>,
+}
+
+// Keep the `Arc<MerkleTree>` use case compiling.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MerkleTree>();
+    assert_send_sync::<MerkleTree<String, DomainSeparated<Sha256Hasher>>>();
+};
+
+#[derive(Debug, Clone)]
+pub struct MerkleNode<M = ()> {
+    left: Option<Box<MerkleNode<M>>>,
+    right: Option<Box<MerkleNode<M>>>,
+    hash: Vec<u8>,
+    metadata: Option<M>,
+    /// Leaves under this node, kept so sizes and index lookups do not have to
+    /// walk the subtree.
+    leaf_count: usize,
+}
+
+impl<M> MerkleNode<M> {
+    /// A leaf node with the given, already computed, hash.
+    pub fn leaf(hash: Vec<u8>) -> Self {
+        MerkleNode {
+            left: None,
+            right: None,
+            hash,
+            metadata: None,
+            leaf_count: 1,
+        }
+    }
+
+    /// An internal node over `left` and `right`. The hash is stored as given
+    /// and is not checked against the children.
+    pub fn internal(left: MerkleNode<M>, right: MerkleNode<M>, hash: Vec<u8>) -> Self {
+        Self::join(Box::new(left), Box::new(right), hash)
+    }
+
+    fn join(left: Box<MerkleNode<M>>, right: Box<MerkleNode<M>>, hash: Vec<u8>) -> Self {
+        MerkleNode {
+            leaf_count: left.leaf_count + right.leaf_count,
+            left: Some(left),
+            right: Some(right),
+            hash,
+            metadata: None,
+        }
+    }
+
+    pub fn hash(&self) -> &Vec<u8> {
+        &self.hash
+    }
+}
+
+impl<M: Clone, H: MerkleHasher> MerkleTree<M, H> {
+    pub fn new(datas: &[Vec<u8>]) -> Self {
+        let leaf_nodes = datas
+            .iter()
+            .map(|data| Self::create_new_data_node(data, None))
+            .collect::<Vec<MerkleNode<M>>>();
+
+        Self::from_built_root(Self::build_tree(&leaf_nodes))
+    }
+
+    /// An empty tree expecting about `n` appends. Nodes are boxed one by one
+    /// and cannot be allocated ahead, so the hint pre-sizes the storage that
+    /// grows by an entry on every append, the `historical_roots` list.
+    pub fn with_capacity(n: usize) -> Self {
+        let mut tree = Self::new(&[]);
+        tree.reserve(n);
+        tree
+    }
+
+    /// Reserves room for at least `additional` more appends in the
+    /// `historical_roots` list and, if enabled, the sorted leaf index; see
+    /// `with_capacity`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.historical_roots.reserve(additional);
+        if let Some(sorted_index) = &mut self.sorted_index {
+            sorted_index.reserve(additional);
+        }
+    }
+
+    /// Like `new`, but builds with the given strategy; `BuildStrategy::TopDown`
+    /// is the same as `new`.
+    pub fn new_with_strategy(datas: &[Vec<u8>], strategy: BuildStrategy) -> Self {
+        let leaf_nodes = datas
+            .iter()
+            .map(|data| Self::create_new_data_node(data, None))
+            .collect::<Vec<MerkleNode<M>>>();
+
+        match strategy {
+            BuildStrategy::TopDown => Self::from_built_root(Self::build_tree(&leaf_nodes)),
+            BuildStrategy::BottomUp => {
+                let mut tree = Self::from_built_root(Self::build_bottom_up(leaf_nodes));
+                tree.append_only = true;
+                tree
+            }
+        }
+    }
+
+    /// Like `new`, but also returns how many hash operations the build
+    /// performed, counted as calls into `H`. Every leaf and every internal
+    /// node is hashed exactly once, so an N-leaf tree costs `2N - 1` hashes.
+    pub fn new_counting_hashes(datas: &[Vec<u8>]) -> (Self, usize) {
+        let before = Counting::<H>::calls();
+        let counted = MerkleTree::<M, Counting<H>>::new(datas);
+        let hash_count = Counting::<H>::calls() - before;
+        let tree = MerkleTree {
+            root: counted.root,
+            historical_roots: counted.historical_roots,
+            append_only: counted.append_only,
+            bind_index: counted.bind_index,
+            normalize: counted.normalize,
+            empty_leaves: counted.empty_leaves,
+            sorted_index: counted.sorted_index,
+            hasher: PhantomData,
+        };
+        (tree, hash_count)
+    }
+
+    /// Builds a tree where every leaf carries its own metadata. The resulting
+    /// root is identical to `new` over the same data.
+    pub fn with_metadata(datas: &[(Vec<u8>, M)]) -> Self {
+        let leaf_nodes = datas
+            .iter()
+            .map(|(data, metadata)| Self::create_new_data_node(data, Some(metadata.clone())))
+            .collect::<Vec<MerkleNode<M>>>();
+
+        Self::from_built_root(Self::build_tree(&leaf_nodes))
+    }
+
+    /// Builds a tree over leaf hashes that were already computed, e.g. with
+    /// `hash_leaf`, without hashing them again.
+    pub fn from_leaf_hashes(leaf_hashes: &[Vec<u8>]) -> Self {
+        let leaf_nodes = leaf_hashes
+            .iter()
+            .map(|hash| Self::create_new_hash_node(hash.clone(), None))
+            .collect::<Vec<MerkleNode<M>>>();
+
+        Self::from_built_root(Self::build_tree(&leaf_nodes))
+    }
+
+    /// Builds a tree over leaf nodes made elsewhere, e.g. with
+    /// `MerkleNode::leaf` under the caller's own leaf hashing. Only the
+    /// internal nodes are computed, with this tree's node hasher and the
+    /// layout of `new`.
+    pub fn from_leaves(leaves: Vec<MerkleNode<M>>) -> Self {
+        Self::from_built_root(Self::build_tree(&leaves))
+    }
+
+    /// Commits to `datas` as a set: leaf hashes are sorted and deduplicated
+    /// before building, so any order or repetition of the same elements
+    /// gives the same root. Leaf indices then refer to sorted order.
+    pub fn commit_set(datas: &[Vec<u8>]) -> Self {
+        let mut leaf_hashes = datas
+            .iter()
+            .map(|data| Self::hash_leaf(data))
+            .collect::<Vec<Vec<u8>>>();
+        leaf_hashes.sort_unstable();
+        leaf_hashes.dedup();
+        Self::from_leaf_hashes(&leaf_hashes)
+    }
+
+    /// Wraps an already-built node structure as a tree, as-is. Hashes are
+    /// not recomputed and consistency is not checked, so this can be used to
+    /// load trees from external formats or to build deliberately malformed
+    /// trees; use `verify_leaf_path` to detect inconsistencies.
+    pub fn from_root_node(node: MerkleNode<M>) -> Self {
+        Self::from_built_root(Some(Box::new(node)))
+    }
+
+    /// Rebuilds a tree from leaf hashes packed back to back, as produced by
+    /// `leaf_hashes_packed`. Returns `None` if `hash_len` is zero or does not
+    /// divide the buffer length.
+    pub fn from_packed_leaf_hashes(buf: &[u8], hash_len: usize) -> Option<Self> {
+        if hash_len == 0 || !buf.len().is_multiple_of(hash_len) {
+            return None;
+        }
+        let leaf_hashes = buf
+            .chunks_exact(hash_len)
+            .map(|hash| hash.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+        Some(Self::from_leaf_hashes(&leaf_hashes))
+    }
+
+    fn from_built_root(root: Option<Box<MerkleNode<M>>>) -> Self {
+        let historical_roots = root.iter().map(|root| root.hash.clone()).collect();
+        MerkleTree {
+            append_only: root.is_none(),
+            root,
+            historical_roots,
+            bind_index: false,
+            normalize: None,
+            empty_leaves: EmptyLeaves::Hash,
+            sorted_index: None,
+            hasher: PhantomData,
+        }
+    }
+
+    fn build_tree(nodes: &[MerkleNode<M>]) -> Option<Box<MerkleNode<M>>> {
+        Self::build_tree_split(nodes, |len| len / 2)
+    }
+
+    /// Pairs adjacent nodes level by level until one is left, carrying an odd
+    /// last node up unchanged.
+    fn build_bottom_up(mut level: Vec<MerkleNode<M>>) -> Option<Box<MerkleNode<M>>> {
+        while level.len() > 1 {
+            let mut next = Vec::with_capacity(level.len().div_ceil(2));
+            let mut nodes = level.into_iter();
+            while let Some(left) = nodes.next() {
+                match nodes.next() {
+                    Some(right) => {
+                        let hash = Self::hash_nodes(&left.hash, &right.hash);
+                        next.push(MerkleNode::internal(left, right, hash));
+                    }
+                    None => next.push(left),
+                }
+            }
+            level = next;
+        }
+        level.pop().map(Box::new)
+    }
+
+    /// Builds over `nodes` in the layout `append` maintains.
+    fn build_append_only_tree(nodes: &[MerkleNode<M>]) -> Option<Box<MerkleNode<M>>> {
+        Self::build_tree_split(nodes, Self::append_only_split)
+    }
+
+    /// Builds top-down, giving the left child the first `split(len)` nodes.
+    fn build_tree_split(
+        nodes: &[MerkleNode<M>],
+        split: fn(usize) -> usize,
+    ) -> Option<Box<MerkleNode<M>>> {
+        if nodes.is_empty() {
+            return None;
+        }
+        if nodes.len() == 1 {
+            return Some(Box::new(nodes[0].clone()));
+        }
+
+        let mid_node = split(nodes.len());
+        let left_child = Self::build_tree_split(&nodes[..mid_node], split)?;
+        let right_child = Self::build_tree_split(&nodes[mid_node..], split)?;
+
+        let hash = Self::hash_nodes(&left_child.hash, &right_child.hash);
+        Some(Box::new(MerkleNode::join(left_child, right_child, hash)))
+    }
+
+    /// Inserts a leaf next to the leftmost one. Returns `false`, leaving the
+    /// tree unchanged, if that would shift leaves of a tree built with
+    /// `MerkleTreeBuilder::bind_index`, whose hashes are bound to the index
+    /// they were added at, or if `data` is empty and the tree was built with
+    /// `EmptyLeaves::Reject`.
+    pub fn insert(&mut self, data: &[u8]) -> bool {
+        if self.shifts_bound_leaves() || self.rejects(data) {
+            return false;
+        }
+        let new_node = Self::create_new_hash_node(self.inserted_leaf_hash(data), None);
+        self.append_node(new_node);
+        true
+    }
+
+    /// Like `insert`, attaching `metadata` to the new leaf.
+    pub fn insert_with_metadata(&mut self, data: &[u8], metadata: M) -> bool {
+        if self.shifts_bound_leaves() || self.rejects(data) {
+            return false;
+        }
+        let new_node = Self::create_new_hash_node(self.inserted_leaf_hash(data), Some(metadata));
+        self.append_node(new_node);
+        true
+    }
+
+    /// Whether an insert would move already bound leaves: it lands at index
+    /// 1, so every later leaf shifts once there are two or more.
+    fn shifts_bound_leaves(&self) -> bool {
+        self.bind_index && self.len() > 1
+    }
+
+    /// `insert` pairs the new leaf with the leftmost one, so it lands at
+    /// index 1 of a non-empty tree.
+    fn inserted_leaf_hash(&self, data: &[u8]) -> Vec<u8> {
+        self.leaf_data_hash(usize::from(self.root.is_some()), data)
+    }
+
+    /// Inserts a pre-computed leaf hash without re-hashing it, so
+    /// `insert_hash(hash_leaf(data))` yields the same tree as `insert(data)`.
+    /// Refused like `insert` on bound trees.
+    pub fn insert_hash(&mut self, leaf_hash: Vec<u8>) -> bool {
+        if self.shifts_bound_leaves() {
+            return false;
+        }
+        let new_node = Self::create_new_hash_node(leaf_hash, None);
+        self.append_node(new_node);
+        true
+    }
+
+    /// Appends a leaf after the current last leaf, keeping the append-only
+    /// layout: the left subtree of every node holds the largest power of two
+    /// of leaves that fits, as in RFC 6962. A tree grown only by `append`
+    /// (from empty, or from a power-of-two `new`) has, at every size, the same
+    /// root as it had when it was that size, which is what
+    /// `generate_historical_proof` relies on. Returns `false`, leaving the
+    /// tree unchanged, if `data` is empty and the tree was built with
+    /// `EmptyLeaves::Reject`.
+    pub fn append(&mut self, data: &[u8]) -> bool {
+        if self.rejects(data) {
+            return false;
+        }
+        let leaf_count = Self::leaf_count(&self.root);
+        let leaf_hash = self.leaf_data_hash(leaf_count, data);
+        if let Some(sorted_index) = &mut self.sorted_index {
+            sorted_index.insert(leaf_hash.clone(), leaf_count);
+        }
+        let new_node = Self::create_new_hash_node(leaf_hash, None);
+        if !self.append_only {
+            self.append_only = Self::has_append_only_shape(self.root.as_deref());
+        }
+        self.root = Some(match self.root.take() {
+            None => Box::new(new_node),
+            Some(root) => Self::append_rightmost(root, new_node),
+        });
+        if let Some(root) = &self.root {
+            self.historical_roots.push(root.hash.clone());
+        }
+        true
+    }
+
+    /// Appends all leaves of `subtree` after the current last leaf. When the
+    /// tree and the subtree are both perfect and of the same size they are
+    /// linked under a new root with a single hash; otherwise the tree is
+    /// rebuilt as `new` over the combined leaves.
+    pub fn append_subtree(&mut self, subtree: MerkleNode<M>) {
+        let leaf_count = Self::leaf_count(&self.root);
+        let subtree_count = Self::count_leaves(&subtree);
+        let linkable = leaf_count == subtree_count
+            && leaf_count.is_power_of_two()
+            && self.is_perfect()
+            && Self::perfect_height(&subtree).is_some();
+
+        self.root = match self.root.take() {
+            None => {
+                self.append_only = Self::has_append_only_shape(Some(&subtree));
+                Some(Box::new(subtree))
+            }
+            Some(root) if linkable => {
+                let hash = Self::hash_nodes(&root.hash, &subtree.hash);
+                self.append_only = true;
+                Some(Box::new(MerkleNode::join(root, Box::new(subtree), hash)))
+            }
+            root => {
+                let subtree = Some(Box::new(subtree));
+                let mut leaves = Vec::with_capacity(leaf_count + subtree_count);
+                Self::collect_leaves(&root, &mut leaves);
+                Self::collect_leaves(&subtree, &mut leaves);
+                let leaf_nodes = leaves.into_iter().cloned().collect::<Vec<MerkleNode<M>>>();
+                self.append_only = false;
+                Self::build_tree(&leaf_nodes)
+            }
+        };
+        if let Some(root) = &self.root {
+            self.historical_roots.push(root.hash.clone());
+        }
+        self.reindex();
+    }
+
+    fn append_rightmost(
+        mut node: Box<MerkleNode<M>>,
+        new_node: MerkleNode<M>,
+    ) -> Box<MerkleNode<M>> {
+        let leaf_count = Self::count_leaves(&node);
+        if leaf_count.is_power_of_two() {
+            let hash = Self::hash_nodes(&node.hash, &new_node.hash);
+            return Box::new(MerkleNode::join(node, Box::new(new_node), hash));
+        }
+
+        let right = node.right.take().unwrap();
+        node.right = Some(Self::append_rightmost(right, new_node));
+        node.leaf_count += 1;
+        node.hash = Self::hash_nodes(
+            &node.left.as_ref().unwrap().hash,
+            &node.right.as_ref().unwrap().hash,
+        );
+        node
+    }
+
+    fn append_node(&mut self, new_node: MerkleNode<M>) {
+        let current_root = self.root.take();
+        self.append_only = false;
+
+        self.root = self.insert_node(new_node, current_root);
+        if let Some(root) = &self.root {
+            self.historical_roots.push(root.hash.clone());
+        }
+        // The new leaf lands at index 1 and shifts every later leaf.
+        self.reindex();
+    }
+
+    fn insert_node(
+        &self,
+        new_node: MerkleNode<M>,
+        current_root: Option<Box<MerkleNode<M>>>,
+    ) -> Option<Box<MerkleNode<M>>> {
+        match current_root {
+            None => Some(Box::new(new_node)),
+            Some(mut node) => {
+                if node.left.is_none() && node.right.is_none() {
+                    let hash = Self::hash_nodes(&node.hash, &new_node.hash);
+                    Some(Box::new(MerkleNode::join(node, Box::new(new_node), hash)))
+                } else {
+                    let child_side = if node.left.is_some() {
+                        &mut node.left
+                    } else {
+                        &mut node.right
+                    };
+                    *child_side = self.insert_node(new_node, child_side.take());
+                    let (left, right) = (node.left?, node.right?);
+                    let hash = Self::hash_nodes(&left.hash, &right.hash);
+                    Some(Box::new(MerkleNode::join(left, right, hash)))
+                }
+            }
+        }
+    }
+
+    /// Removes the leaf at `index` and rebuilds the tree over the remaining
+    /// leaves, so the root matches `new` over them. Returns `false` if `index`
+    /// is out of range, or if the tree was built with
+    /// `MerkleTreeBuilder::bind_index` and `index` is not the last leaf, as
+    /// the later leaves would move away from the index they are bound to.
+    pub fn remove_at(&mut self, index: usize) -> bool {
+        if self.bind_index && index + 1 < self.len() {
+            return false;
+        }
+        let mut leaf_nodes = self
+            .leaf_nodes()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<MerkleNode<M>>>();
+        if index >= leaf_nodes.len() {
+            return false;
+        }
+
+        leaf_nodes.remove(index);
+        self.root = Self::build_tree(&leaf_nodes);
+        self.append_only = false;
+        self.reindex();
+        true
+    }
+
+    /// Replaces the data of the leaf at `index`, rehashing only the nodes on
+    /// its path. Returns `false` if `index` is out of range, or if `data` is
+    /// empty and the tree was built with `EmptyLeaves::Reject`.
+    pub fn update(&mut self, index: usize, data: &[u8]) -> bool {
+        if index >= Self::leaf_count(&self.root) || self.rejects(data) {
+            return false;
+        }
+        let leaf_hash = self.leaf_data_hash(index, data);
+        match self.root.as_deref_mut() {
+            Some(root) => {
+                Self::update_node(root, index, leaf_hash);
+                self.reindex();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The root `update(index, new_data)` would produce, computed from the
+    /// leaf's path without changing the tree. Returns `None` if `index` is out
+    /// of range.
+    pub fn preview_update(&self, index: usize, new_data: &[u8]) -> Option<Vec<u8>> {
+        let proof = self.generate_proof_by_index(index)?;
+        let leaf_hash = self.leaf_data_hash(index, new_data);
+        Some(Self::root_from_proof(&leaf_hash, &proof))
+    }
+
+    fn update_node(node: &mut MerkleNode<M>, index: usize, leaf_hash: Vec<u8>) {
+        match (node.left.as_deref_mut(), node.right.as_deref_mut()) {
+            (Some(left), Some(right)) => {
+                let left_count = Self::count_leaves(left);
+                if index < left_count {
+                    Self::update_node(left, index, leaf_hash);
+                } else {
+                    Self::update_node(right, index - left_count, leaf_hash);
+                }
+                node.hash = Self::hash_nodes(&left.hash, &right.hash);
+            }
+            _ => node.hash = leaf_hash,
+        }
+    }
+
+    /// Keeps only the first `n` leaves and rebuilds. A tree in the
+    /// append-only layout, i.e. grown with `append` from empty or from a
+    /// perfect tree, stays in it, so its root equals the historical root at
+    /// size `n`; any other tree is rebuilt as `new` over the kept leaves.
+    /// Truncating to 0 leaves an empty tree.
+    pub fn truncate(&mut self, n: usize) {
+        let mut leaf_nodes = self
+            .leaf_nodes()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<MerkleNode<M>>>();
+        leaf_nodes.truncate(n);
+
+        self.root = if self.append_only {
+            Self::build_append_only_tree(&leaf_nodes)
+        } else {
+            Self::build_tree(&leaf_nodes)
+        };
+        self.reindex();
+    }
+
+    /// Builds a `SortedLeafIndex` over the current leaves and keeps it up to
+    /// date from now on, so `contains` and `index_of` use binary search.
+    /// Appends update it in place; other mutations rebuild it, which pays off
+    /// for trees that are queried far more than changed. Trees built with
+    /// `MerkleTreeBuilder::bind_index` hash leaves by position and keep
+    /// using the linear lookup.
+    pub fn enable_sorted_index(&mut self) {
+        self.sorted_index = Some(SortedLeafIndex::default());
+        self.reindex();
+    }
+
+    /// The sorted leaf index, if enabled.
+    pub fn sorted_index(&self) -> Option<&SortedLeafIndex> {
+        self.sorted_index.as_ref()
+    }
+
+    fn reindex(&mut self) {
+        if self.sorted_index.is_some() {
+            let leaves = self.leaf_nodes();
+            let sorted_index = SortedLeafIndex::new(leaves.iter().map(|leaf| &leaf.hash));
+            self.sorted_index = Some(sorted_index);
+        }
+    }
+
+    /// Whether `data` is one of the tree's leaves. Internal node hashes are
+    /// never matched, so the concatenation of two leaf hashes is not
+    /// reported as contained. A sorted index only speeds the lookup up; the
+    /// answer is the same with or without one.
+    pub fn contains(&self, data: &[u8]) -> bool {
+        self.leaf_position(data).is_some()
+    }
+
+    /// All nodes in breadth-first order: the root, then its children left to
+    /// right, and so on down. In a perfect tree the leaves come last.
+    pub fn iter_bfs(&self) -> impl Iterator<Item = &MerkleNode<M>> {
+        let mut queue = self
+            .root
+            .iter()
+            .map(Box::as_ref)
+            .collect::<VecDeque<&MerkleNode<M>>>();
+        std::iter::from_fn(move || {
+            let node = queue.pop_front()?;
+            queue.extend(node.left.as_deref());
+            queue.extend(node.right.as_deref());
+            Some(node)
+        })
+    }
+
+    /// Distance from the root to the shallowest node whose hash is `hash`,
+    /// so the root is at depth 0. Returns `None` if no node matches.
+    pub fn depth_of(&self, hash: &[u8]) -> Option<usize> {
+        let mut level = self
+            .root
+            .iter()
+            .map(Box::as_ref)
+            .collect::<Vec<&MerkleNode<M>>>();
+        let mut depth = 0;
+        while !level.is_empty() {
+            if level.iter().any(|node| node.hash == hash) {
+                return Some(depth);
+            }
+            level = level
+                .iter()
+                .flat_map(|node| node.left.iter().chain(node.right.iter()))
+                .map(Box::as_ref)
+                .collect();
+            depth += 1;
+        }
+        None
+    }
+
+    /// Generates an inclusion proof for the first leaf whose hash matches
+    /// `data`. Only leaves are considered, never internal nodes. In a
+    /// single-leaf tree the leaf is the root and the proof is empty.
+    pub fn generate_proof(&self, data: &[u8]) -> Option<Proof> {
+        let index = self.leaf_position(data)?;
+        self.generate_proof_by_index(index)
+    }
+
+    /// Index of the first leaf holding `data`, found by binary search when a
+    /// sorted index is enabled.
+    pub fn index_of(&self, data: &[u8]) -> Option<usize> {
+        self.leaf_position(data)
+    }
+
+    fn leaf_position(&self, data: &[u8]) -> Option<usize> {
+        if let (Some(sorted_index), false) = (&self.sorted_index, self.bind_index) {
+            return sorted_index.position(&self.leaf_data_hash(0, data));
+        }
+        let leaves = self.leaf_nodes();
+        if self.bind_index {
+            return (0..leaves.len())
+                .find(|&index| leaves[index].hash == self.leaf_data_hash(index, data));
+        }
+        let data_hash = self.leaf_data_hash(0, data);
+        leaves.iter().position(|leaf| leaf.hash == data_hash)
+    }
+
+    /// Issues a fresh proof for `data` at wherever it sits now, for clients
+    /// whose proof went stale after `remove_at` or another rebuild moved the
+    /// leaf. Returns `None` if the leaf is no longer in the tree.
+    pub fn reissue_proof(&self, data: &[u8]) -> Option<Proof> {
+        self.generate_proof(data)
+    }
+
+    /// Generates the proof for the leaf at `index` together with the leaf's
+    /// data, fetched from `resolver` by leaf hash. Returns `None` if `index`
+    /// is out of range, the resolver has no data for the leaf, or the data it
+    /// returns does not hash to the leaf.
+    pub fn generate_proof_with_data(
+        &self,
+        index: usize,
+        resolver: &impl LeafResolver,
+    ) -> Option<(Vec<u8>, Proof)> {
+        let leaf_hash = &self.path_to_leaf(index)?.last()?.hash;
+        let data = resolver.resolve(leaf_hash)?;
+        if self.leaf_data_hash(index, &data) != *leaf_hash {
+            return None;
+        }
+        Some((data, self.generate_proof_by_index(index)?))
+    }
+
+    /// Generates a proof for `data` and asserts it verifies against the
+    /// tree's own root before returning it. Returns `None` if `data` is not a
+    /// leaf.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generated proof does not verify, which means the tree is
+    /// internally inconsistent.
+    pub fn prove_and_verify(&self, data: &[u8]) -> Option<Proof> {
+        let proof = self.generate_proof(data)?;
+        let root = self.root_hash()?;
+        let leaf_hash = self.leaf_data_hash(proof.leaf_index, data);
+        assert!(
+            Self::root_from_proof(&leaf_hash, &proof) == *root,
+            "generated proof does not verify against the tree's root"
+        );
+        Some(proof)
+    }
+
+    /// Generates an inclusion proof for the leaf at `index`, listing siblings
+    /// from the leaf up to the root.
+    pub fn generate_proof_by_index(&self, index: usize) -> Option<Proof> {
+        let path = self.path_to_leaf(index)?;
+
+        let mut siblings = Vec::with_capacity(path.len() - 1);
+        for pair in path.windows(2).rev() {
+            let (parent, child) = (pair[0], pair[1]);
+            let (left, right) = (parent.left.as_ref()?, parent.right.as_ref()?);
+            let is_left = !std::ptr::eq(left.as_ref(), child);
+            let sibling = if is_left { left } else { right };
+            siblings.push(ProofNode {
+                hash: sibling.hash.clone(),
+                is_left,
+            });
+        }
+
+        // With identical leaves the sibling hash can equal the leaf hash, so
+        // make sure a leaf-level sibling really is the neighbouring leaf,
+        // looked up by index rather than through the shared parent. Only
+        // stored hashes are compared: trees from `from_root_node` may be
+        // deliberately inconsistent, and `validate` reports that instead.
+        if let (Some(first), [.., parent, _]) = (siblings.first(), &path[..]) {
+            let sibling = if first.is_left {
+                &parent.left
+            } else {
+                &parent.right
+            };
+            if sibling
+                .as_ref()
+                .is_some_and(|sibling| sibling.leaf_count == 1)
+            {
+                let neighbour = if first.is_left { index - 1 } else { index + 1 };
+                debug_assert_eq!(
+                    self.path_to_leaf(neighbour)
+                        .and_then(|path| path.last().map(|leaf| &leaf.hash)),
+                    Some(&first.hash),
+                    "leaf-level sibling of leaf {} is not its neighbour",
+                    index
+                );
+            }
+        }
+
+        Some(Proof {
+            leaf_index: index,
+            tree_size: Self::leaf_count(&self.root),
+            siblings,
+        })
+    }
+
+    /// Number of siblings in the proof for the leaf at `index`, i.e. the
+    /// leaf's depth, which varies between leaves of an unbalanced tree.
+    /// Returns `None` if `index` is out of range.
+    pub fn proof_len(&self, index: usize) -> Option<usize> {
+        self.path_to_leaf(index).map(|path| path.len() - 1)
+    }
+
+    /// Brings a stale proof for the leaf at `index` up to date after other
+    /// leaves changed, rewriting only the sibling hashes that differ from the
+    /// current tree. If the path itself changed shape, the proof is resized
+    /// to match. Leaves the proof untouched if `index` is out of range.
+    pub fn refresh_proof(&self, stale: &mut Proof, index: usize) {
+        let path = match self.path_to_leaf(index) {
+            Some(path) => path,
+            None => return,
+        };
+
+        stale.leaf_index = index;
+        stale.tree_size = Self::leaf_count(&self.root);
+        stale.siblings.truncate(path.len() - 1);
+        for (level, pair) in path.windows(2).rev().enumerate() {
+            let (parent, child) = (pair[0], pair[1]);
+            let (left, right) = match (&parent.left, &parent.right) {
+                (Some(left), Some(right)) => (left, right),
+                _ => return,
+            };
+            let is_left = !std::ptr::eq(left.as_ref(), child);
+            let sibling = if is_left { left } else { right };
+
+            match stale.siblings.get_mut(level) {
+                Some(node) => {
+                    if node.hash != sibling.hash {
+                        node.hash.clone_from(&sibling.hash);
+                    }
+                    node.is_left = is_left;
+                }
+                None => stale.siblings.push(ProofNode {
+                    hash: sibling.hash.clone(),
+                    is_left,
+                }),
+            }
+        }
+    }
+
+    /// Keeps a long-lived proof for the leaf at `index` valid as the tree
+    /// grows with `append`, by `refresh_proof`. In the append-only layout an
+    /// append only changes the rightmost spine, so the siblings inside the
+    /// leaf's complete subtree stay as they are and only the upper ones are
+    /// rewritten or added. Returns `false`, leaving the proof untouched, if
+    /// the tree is not in that layout or `index` is out of range; the proof
+    /// then has to be regenerated.
+    pub fn update_proof_on_append(&self, proof: &mut Proof, index: usize) -> bool {
+        if !self.append_only || index >= self.len() {
+            return false;
+        }
+        self.refresh_proof(proof, index);
+        true
+    }
+
+    /// Generates a proof for the leaf at `index` that verifies against the
+    /// root the tree had when it held only its first `as_of_size` leaves.
+    ///
+    /// This requires the append-only layout (see `append`); `None` is
+    /// returned if the tree is not in that layout or either argument is out
+    /// of range.
+    pub fn generate_historical_proof(&self, index: usize, as_of_size: usize) -> Option<Proof> {
+        if !self.append_only && !Self::has_append_only_shape(self.root.as_deref()) {
+            return None;
+        }
+        let leaf_hashes = self
+            .leaf_nodes()
+            .iter()
+            .map(|leaf| leaf.hash.clone())
+            .collect::<Vec<Vec<u8>>>();
+        if index >= as_of_size || as_of_size > leaf_hashes.len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        Self::append_only_path(&leaf_hashes[..as_of_size], index, &mut siblings);
+        Some(Proof {
+            leaf_index: index,
+            tree_size: as_of_size,
+            siblings,
+        })
+    }
+
+    /// Whether a tree built some other way ended up in the append-only
+    /// layout, which holds for the empty tree and for perfect ones. Only a
+    /// power-of-two leaf count can be perfect, so other sizes are answered
+    /// without walking the tree. Only the shape is checked; stored hashes are
+    /// taken as they are.
+    fn has_append_only_shape(root: Option<&MerkleNode<M>>) -> bool {
+        root.is_none_or(|root| {
+            root.leaf_count.is_power_of_two() && Self::perfect_height(root).is_some()
+        })
+    }
+
+    /// Root of the append-only layout over `leaf_hashes`: the left subtree
+    /// takes the largest power of two strictly below the leaf count.
+    fn append_only_root(leaf_hashes: &[Vec<u8>]) -> Option<Vec<u8>> {
+        match leaf_hashes.len() {
+            0 => None,
+            1 => Some(leaf_hashes[0].clone()),
+            len => {
+                let split = Self::append_only_split(len);
+                let left = Self::append_only_root(&leaf_hashes[..split])?;
+                let right = Self::append_only_root(&leaf_hashes[split..])?;
+                Some(Self::hash_nodes(&left, &right))
+            }
+        }
+    }
+
+    fn append_only_path(leaf_hashes: &[Vec<u8>], index: usize, siblings: &mut Vec<ProofNode>) {
+        if leaf_hashes.len() <= 1 {
+            return;
+        }
+
+        let split = Self::append_only_split(leaf_hashes.len());
+        let (left, right) = leaf_hashes.split_at(split);
+        if index < split {
+            Self::append_only_path(left, index, siblings);
+            siblings.push(ProofNode {
+                hash: Self::append_only_root(right).unwrap(),
+                is_left: false,
+            });
+        } else {
+            Self::append_only_path(right, index - split, siblings);
+            siblings.push(ProofNode {
+                hash: Self::append_only_root(left).unwrap(),
+                is_left: true,
+            });
+        }
+    }
+
+    fn append_only_split(len: usize) -> usize {
+        1 << (len - 1).ilog2()
+    }
+
+    /// Roots the tree has had, oldest first: the root it was built with
+    /// followed by the root after every insert. Rebuilding operations such as
+    /// `remove_at` do not add entries.
+    pub fn historical_roots(&self) -> &[Vec<u8>] {
+        &self.historical_roots
+    }
+
+    /// The root after appending each of `datas` in turn to an empty tree, so
+    /// entry `i` is the append-only root over the first `i + 1` leaves. The
+    /// last entry matches `compute_root` only for power-of-two counts; see
+    /// `append`.
+    pub fn roots_over_appends(datas: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let mut tree = Self::with_capacity(datas.len());
+        for data in datas {
+            tree.append(data);
+        }
+        tree.historical_roots
+    }
+
+    /// Checks that `leaf` was included in the tree at the version whose root
+    /// is `historical_root`, e.g. one taken from `historical_roots`, given a
+    /// proof issued against that version.
+    pub fn verify_historical(
+        historical_root: &[u8],
+        leaf: &[u8],
+        proof: &Proof,
+    ) -> Result<bool, VerifyError> {
+        Self::verify_proof(historical_root, leaf, proof)
+    }
+
+    /// Checks that `datas` produce `claimed_root` under this tree's hasher
+    /// and layout, without building the tree.
+    pub fn verify_root(datas: &[Vec<u8>], claimed_root: &[u8]) -> bool {
+        let leaf_hashes = datas
+            .iter()
+            .map(|data| Self::hash_leaf(data))
+            .collect::<Vec<Vec<u8>>>();
+        root_of_leaf_hashes::<H>(&leaf_hashes).is_some_and(|root| root == claimed_root)
+    }
+
+    /// Checks that `proof` links `data` to `root`, returning `Ok(false)` if
+    /// it does not. A root or sibling whose length differs from the leaf
+    /// hash's is an error, `VerifyError::HashLengthMismatch`, rather than a
+    /// plain mismatch. An empty proof verifies exactly when the leaf hash is
+    /// the root, as for a single-leaf tree.
+    pub fn verify_proof(root: &[u8], data: &[u8], proof: &Proof) -> Result<bool, VerifyError> {
+        match Self::verify_proof_detailed(root, data, proof) {
+            Ok(()) => Ok(true),
+            Err(VerifyError::RootMismatch | VerifyError::EmptyProof) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Checks a proof from a tree built with `MerkleTreeBuilder::bind_index`,
+    /// where `data` must have been committed at position `index`.
+    pub fn verify_proof_at(root: &[u8], index: usize, data: &[u8], proof: &Proof) -> bool {
+        let leaf_hash = Self::hash_leaf_at(index, data);
+        (!proof.is_empty() || leaf_hash == root) && Self::root_from_proof(&leaf_hash, proof) == root
+    }
+
+    /// Like `verify_proof`, but with `reject_leaf_sibling` set also rejects a
+    /// proof whose first sibling equals the leaf hash. Without domain
+    /// separation that shape is how forged proofs over a node's own children
+    /// tend to look, so pass `false` only for trees known to hold identical
+    /// adjacent leaves.
+    pub fn verify_proof_validated(
+        root: &[u8],
+        data: &[u8],
+        proof: &Proof,
+        reject_leaf_sibling: bool,
+    ) -> Result<bool, VerifyError> {
+        if reject_leaf_sibling {
+            let leaf_hash = Self::hash_leaf(data);
+            if proof
+                .siblings
+                .first()
+                .is_some_and(|sibling| sibling.hash == leaf_hash)
+            {
+                return Ok(false);
+            }
+        }
+        Self::verify_proof(root, data, proof)
+    }
+
+    /// Like `verify_proof`, but reports why a proof was rejected.
+    ///
+    /// The expected hash length is that of the leaf hash of `data`, which is
+    /// `MerkleHasher::hash_len` for any hasher reporting its length
+    /// correctly. Trees built by `from_leaf_hashes` or `from_leaves` over
+    /// hashes of another length therefore cannot be verified here; their
+    /// proofs fail with `HashLengthMismatch`.
+    pub fn verify_proof_detailed(
+        root: &[u8],
+        data: &[u8],
+        proof: &Proof,
+    ) -> Result<(), VerifyError> {
+        let leaf_hash = Self::hash_leaf(data);
+        let hash_len = leaf_hash.len();
+        if root.len() != hash_len {
+            return Err(VerifyError::HashLengthMismatch {
+                position: None,
+                expected: hash_len,
+                found: root.len(),
+            });
+        }
+        for (position, sibling) in proof.siblings.iter().enumerate() {
+            if sibling.hash.len() != hash_len {
+                return Err(VerifyError::HashLengthMismatch {
+                    position: Some(position),
+                    expected: hash_len,
+                    found: sibling.hash.len(),
+                });
+            }
+        }
+        if proof.is_empty() && leaf_hash != root {
+            return Err(VerifyError::EmptyProof);
+        }
+
+        if Self::root_from_proof(&leaf_hash, proof) != root {
+            return Err(VerifyError::RootMismatch);
+        }
+        Ok(())
+    }
+
+    /// Verifies `proof` for a sorted-pair tree, where every parent hashes its
+    /// two children smaller first, so sibling sides do not matter and the
+    /// `is_left` flags are ignored.
+    pub fn verify_proof_sorted(root: &[u8], data: &[u8], proof: &Proof) -> bool {
+        let mut hash = Self::hash_leaf(data);
+        for sibling in &proof.siblings {
+            hash = if sibling.hash < hash {
+                Self::hash_nodes(&sibling.hash, &hash)
+            } else {
+                Self::hash_nodes(&hash, &sibling.hash)
+            };
+        }
+        hash == root
+    }
+
+    /// Accepts `proof` if it verifies either with its side bits, as
+    /// `verify_proof`, or as a sorted-pair proof, as `verify_proof_sorted`,
+    /// for callers that take proofs from producers of both kinds. This is a
+    /// convenience and slightly weaker than checking the one mode the
+    /// producer is known to use, since a proof now passes if either reading
+    /// reaches the root.
+    pub fn verify_proof_auto(root: &[u8], data: &[u8], proof: &Proof) -> Result<bool, VerifyError> {
+        Ok(Self::verify_proof(root, data, proof)? || Self::verify_proof_sorted(root, data, proof))
+    }
+
+    /// Verifies `proof` in position-bearing mode: the side of every sibling is
+    /// derived from the proof's `leaf_index` and `tree_size` using the layout
+    /// `new` builds, and the stored `is_left` flags are ignored. A proof
+    /// passes only if `data` sits at exactly that index, so the verifier also
+    /// learns the committed position. Proofs from trees reshaped by `insert`
+    /// or grown with `append` do not follow that layout and are rejected.
+    pub fn verify_proof_positional(root: &[u8], data: &[u8], proof: &Proof) -> bool {
+        let sides = match proof::midpoint_sibling_sides(proof.leaf_index, proof.tree_size) {
+            Some(sides) if sides.len() == proof.len() => sides,
+            _ => return false,
+        };
+
+        let mut hash = Self::hash_leaf(data);
+        for (sibling, is_left) in proof.siblings.iter().zip(sides) {
+            hash = if is_left {
+                Self::hash_nodes(&sibling.hash, &hash)
+            } else {
+                Self::hash_nodes(&hash, &sibling.hash)
+            };
+        }
+        hash == root
+    }
+
+    /// Recomputes the root implied by `proof` once and returns the index of
+    /// the first candidate in `roots` it matches.
+    pub fn which_root(leaf_hash: &[u8], proof: &Proof, roots: &[Vec<u8>]) -> Option<usize> {
+        let root = Self::root_from_proof(leaf_hash, proof);
+        roots.iter().position(|candidate| *candidate == root)
+    }
+
+    /// Whether two proofs, possibly received from different sources, lead
+    /// their leaves to the same root and so come from a single tree.
+    pub fn proofs_share_root(
+        leaf_a: &[u8],
+        proof_a: &Proof,
+        leaf_b: &[u8],
+        proof_b: &Proof,
+    ) -> bool {
+        let root_a = Self::root_from_proof(&Self::hash_leaf(leaf_a), proof_a);
+        let root_b = Self::root_from_proof(&Self::hash_leaf(leaf_b), proof_b);
+        root_a == root_b
+    }
+
+    fn root_from_proof(leaf_hash: &[u8], proof: &Proof) -> Vec<u8> {
+        let mut hash = leaf_hash.to_vec();
+        for sibling in &proof.siblings {
+            hash = if sibling.is_left {
+                Self::hash_nodes(&sibling.hash, &hash)
+            } else {
+                Self::hash_nodes(&hash, &sibling.hash)
+            };
+        }
+        hash
+    }
+
+    /// Returns the metadata attached to the leaf at `index`, counting leaves
+    /// from left to right. Leaves inserted without metadata yield `None`.
+    pub fn leaf_metadata(&self, index: usize) -> Option<&M> {
+        let leaves = self.leaf_nodes();
+        leaves.get(index).and_then(|leaf| leaf.metadata.as_ref())
+    }
+
+    /// All leaf hashes, in order, concatenated into one buffer. With a
+    /// fixed-length hasher every hash takes `H::hash_len()` bytes.
+    pub fn leaf_hashes_packed(&self) -> Vec<u8> {
+        self.leaf_nodes()
+            .iter()
+            .flat_map(|leaf| leaf.hash.iter().copied())
+            .collect()
+    }
+
+    /// Root of the leaves at `indices`, taken in the given order and folded
+    /// with the layout of `new`, as `compute_root` does. Returns `None` if any
+    /// index is out of range or `indices` is empty.
+    pub fn subset_root(&self, indices: &[usize]) -> Option<Vec<u8>> {
+        let leaves = self.leaf_nodes();
+        let leaf_hashes = indices
+            .iter()
+            .map(|&index| leaves.get(index).map(|leaf| leaf.hash.clone()))
+            .collect::<Option<Vec<Vec<u8>>>>()?;
+        root_of_leaf_hashes::<H>(&leaf_hashes)
+    }
+
+    /// A digest over the leaves `[start, end)` plus a proof binding those
+    /// leaves, as a unit, to the root. The digest is `compute_root` over the
+    /// range. The proof's `leaf_index` is `start` and its siblings are the
+    /// hashes of the maximal subtrees outside the range, in left-to-right
+    /// order, with `is_left` set for those before it. Returns `None` for an
+    /// empty or out-of-range span, or if the nodes the verifier recomputes do
+    /// not split at the midpoint as `new` does. Stored hashes are used as
+    /// they are, as by `generate_proof_by_index`.
+    pub fn range_digest(&self, start: usize, end: usize) -> Option<(Vec<u8>, Proof)> {
+        let root = self.root.as_deref()?;
+        if start >= end || end > root.leaf_count {
+            return None;
+        }
+
+        let mut range = Vec::with_capacity(end - start);
+        let mut siblings = Vec::new();
+        Self::range_siblings(root, start, end, &mut range, &mut siblings)?;
+        let proof = Proof {
+            leaf_index: start,
+            tree_size: root.leaf_count,
+            siblings,
+        };
+        Some((root_of_leaf_hashes::<H>(&range)?, proof))
+    }
+
+    /// Walks `node`, collecting the leaf hashes inside `[start, end)` into
+    /// `range` and the hashes of the maximal subtrees outside it into
+    /// `siblings`, where the bounds are already clamped to the subtree.
+    fn range_siblings(
+        node: &MerkleNode<M>,
+        start: usize,
+        end: usize,
+        range: &mut Vec<Vec<u8>>,
+        siblings: &mut Vec<ProofNode>,
+    ) -> Option<()> {
+        if start >= end {
+            siblings.push(ProofNode {
+                hash: node.hash.clone(),
+                is_left: start == node.leaf_count,
+            });
+            return Some(());
+        }
+
+        match (node.left.as_deref(), node.right.as_deref()) {
+            (None, None) => range.push(node.hash.clone()),
+            (Some(left), Some(right)) if left.leaf_count == node.leaf_count / 2 => {
+                let mid = left.leaf_count;
+                Self::range_siblings(left, start.min(mid), end.min(mid), range, siblings)?;
+                Self::range_siblings(
+                    right,
+                    start.max(mid) - mid,
+                    end.max(mid) - mid,
+                    range,
+                    siblings,
+                )?;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Checks a range proof from `range_digest`: `datas` must hash to
+    /// `digest` and, placed at `proof.leaf_index`, combine with the proof's
+    /// outside subtrees into `root`.
+    pub fn verify_range_digest(
+        root: &[u8],
+        datas: &[Vec<u8>],
+        digest: &[u8],
+        proof: &Proof,
+    ) -> bool {
+        let leaf_hashes = datas
+            .iter()
+            .map(|data| Self::hash_leaf(data))
+            .collect::<Vec<Vec<u8>>>();
+        if root_of_leaf_hashes::<H>(&leaf_hashes).is_none_or(|range| range != digest) {
+            return false;
+        }
+        let end = match proof.leaf_index.checked_add(leaf_hashes.len()) {
+            Some(end) if end <= proof.tree_size => end,
+            _ => return false,
+        };
+
+        let mut leaves = &leaf_hashes[..];
+        let mut siblings = proof.siblings.iter();
+        let computed = Self::range_root(
+            proof.tree_size,
+            proof.leaf_index,
+            end,
+            &mut leaves,
+            &mut siblings,
+        );
+        computed.is_some_and(|computed| computed == root)
+            && leaves.is_empty()
+            && siblings.next().is_none()
+    }
+
+    /// Root of a `size`-leaf subtree, taking the range part `[start, end)`
+    /// from `leaves` and everything else from `siblings`.
+    fn range_root<'a>(
+        size: usize,
+        start: usize,
+        end: usize,
+        leaves: &mut &[Vec<u8>],
+        siblings: &mut impl Iterator<Item = &'a ProofNode>,
+    ) -> Option<Vec<u8>> {
+        if start == 0 && end == size {
+            let (range, rest) = leaves.split_at_checked(size)?;
+            *leaves = rest;
+            return root_of_leaf_hashes::<H>(range);
+        }
+        if start >= end {
+            return siblings.next().map(|sibling| sibling.hash.clone());
+        }
+
+        let mid = size / 2;
+        let left = Self::range_root(mid, start.min(mid), end.min(mid), leaves, siblings)?;
+        let right = Self::range_root(
+            size - mid,
+            start.max(mid) - mid,
+            end.max(mid) - mid,
+            leaves,
+            siblings,
+        )?;
+        Some(Self::hash_nodes(&left, &right))
+    }
+
+    /// Root the current leaves would have under `policy`, for checking
+    /// whether another system's convention agrees with this tree. The tree
+    /// is not changed. Returns `None` for an empty tree.
+    pub fn root_under_policy(&self, policy: OddLeafPolicy) -> Option<Vec<u8>> {
+        let mut level = self
+            .leaf_nodes()
+            .iter()
+            .map(|leaf| leaf.hash.clone())
+            .collect::<Vec<Vec<u8>>>();
+        if policy == OddLeafPolicy::Midpoint {
+            return root_of_leaf_hashes::<H>(&level);
+        }
+
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match (pair, policy) {
+                    ([left, right], _) => Self::hash_nodes(left, right),
+                    ([last], OddLeafPolicy::Duplicate) => Self::hash_nodes(last, last),
+                    _ => pair[0].clone(),
+                })
+                .collect();
+        }
+        level.pop()
+    }
+
+    /// Index of the first leaf whose hash differs from `expected`, or `None`
+    /// if the leaves match exactly. Leaf hashes are compared in order,
+    /// stopping at the first difference. The stored subtree hashes are of no
+    /// help here: checking one against `expected` would mean hashing the
+    /// expected leaves under it, which costs more than comparing them. If the
+    /// lengths differ only the common prefix is compared, and a matching
+    /// prefix reports the shorter length.
+    pub fn first_mismatch(&self, expected: &[Vec<u8>]) -> Option<usize> {
+        let leaves = self.leaf_nodes();
+        leaves
+            .iter()
+            .zip(expected)
+            .position(|(leaf, hash)| leaf.hash != *hash)
+            .or_else(|| {
+                (leaves.len() != expected.len()).then_some(leaves.len().min(expected.len()))
+            })
+    }
+
+    /// Returns the indices of all leaves whose hash starts with `prefix`.
+    pub fn leaves_with_prefix(&self, prefix: &[u8]) -> Vec<usize> {
+        self.leaf_nodes()
+            .iter()
+            .enumerate()
+            .filter(|(_, leaf)| leaf.hash.starts_with(prefix))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Number of leaves in the tree.
+    pub fn len(&self) -> usize {
+        Self::leaf_count(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Whether every leaf sits at the same depth, so all proofs have the same
+    /// length. Such a tree always has a power-of-two leaf count; trees grown
+    /// with `insert` usually do not qualify. An empty tree is not perfect.
+    pub fn is_perfect(&self) -> bool {
+        self.root
+            .as_deref()
+            .and_then(Self::perfect_height)
+            .is_some()
+    }
+
+    /// Height of `node` if all its leaves are at the same depth.
+    fn perfect_height(node: &MerkleNode<M>) -> Option<usize> {
+        match (node.left.as_deref(), node.right.as_deref()) {
+            (None, None) => Some(0),
+            (Some(left), Some(right)) => {
+                let height = Self::perfect_height(left)?;
+                (Self::perfect_height(right)? == height).then_some(height + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of distinct leaf hashes. Less than `len` exactly when some leaf
+    /// occurs more than once.
+    pub fn distinct_leaf_count(&self) -> usize {
+        self.leaf_nodes()
+            .iter()
+            .map(|leaf| &leaf.hash)
+            .collect::<HashSet<&Vec<u8>>>()
+            .len()
+    }
+
+    pub fn root_hash(&self) -> Option<&Vec<u8>> {
+        self.root.as_ref().map(|root| &root.hash)
+    }
+
+    /// Hash of the leftmost leaf, found by descending left in O(height).
+    pub fn first_leaf_hash(&self) -> Option<&Vec<u8>> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some(&node.hash)
+    }
+
+    /// Hash of the rightmost leaf, found by descending right in O(height).
+    pub fn last_leaf_hash(&self) -> Option<&Vec<u8>> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some(&node.hash)
+    }
+
+    /// Recomputes the hashes on the path from the leaf at `index` up to the
+    /// root using only the children of each node, without trusting the stored
+    /// hashes along the way. Returns `false` if any recomputed hash disagrees
+    /// with the stored one or the walk does not reach `root_hash`.
+    pub fn verify_leaf_path(&self, index: usize) -> bool {
+        let path = match self.path_to_leaf(index) {
+            Some(path) => path,
+            None => return false,
+        };
+
+        let mut hash = path[path.len() - 1].hash.clone();
+        for pair in path.windows(2).rev() {
+            let (parent, child) = (pair[0], pair[1]);
+            let (left, right) = match (&parent.left, &parent.right) {
+                (Some(left), Some(right)) => (left, right),
+                _ => return false,
+            };
+            hash = if std::ptr::eq(left.as_ref(), child) {
+                Self::hash_nodes(&hash, &right.hash)
+            } else {
+                Self::hash_nodes(&left.hash, &hash)
+            };
+            if hash != parent.hash {
+                return false;
+            }
+        }
+
+        self.root_hash() == Some(&hash)
+    }
+
+    /// Checks every internal node's stored hash against the hash of its
+    /// children, walking the tree in pre-order. Returns the first node whose
+    /// hash disagrees, so trees loaded with `from_root_node` can be audited.
+    pub fn validate(&self) -> Result<(), &MerkleNode<M>> {
+        match self.root.as_deref() {
+            Some(root) => Self::validate_node(root),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_node(node: &MerkleNode<M>) -> Result<(), &MerkleNode<M>> {
+        match (node.left.as_deref(), node.right.as_deref()) {
+            (None, None) => Ok(()),
+            (Some(left), Some(right)) => {
+                if Self::hash_nodes(&left.hash, &right.hash) != node.hash {
+                    return Err(node);
+                }
+                Self::validate_node(left)?;
+                Self::validate_node(right)
+            }
+            _ => Err(node),
+        }
+    }
+
+    /// Hash of the lowest node whose subtree contains both leaf `i` and leaf
+    /// `j`. Returns `None` if either index is out of range.
+    pub fn lca_hash(&self, i: usize, j: usize) -> Option<Vec<u8>> {
+        let leaf_count = Self::leaf_count(&self.root);
+        if i >= leaf_count || j >= leaf_count {
+            return None;
+        }
+
+        let (mut low, mut high) = (i.min(j), i.max(j));
+        let mut node = self.root.as_deref()?;
+        while let (Some(left), Some(right)) = (node.left.as_deref(), node.right.as_deref()) {
+            let left_count = Self::count_leaves(left);
+            if high < left_count {
+                node = left;
+            } else if low >= left_count {
+                low -= left_count;
+                high -= left_count;
+                node = right;
+            } else {
+                break;
+            }
+        }
+        Some(node.hash.clone())
+    }
+
+    /// Returns the nodes from the root down to the leaf at `index`.
+    fn path_to_leaf(&self, index: usize) -> Option<Vec<&MerkleNode<M>>> {
+        if index >= Self::leaf_count(&self.root) {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut current = self.root.as_deref()?;
+        let mut index = index;
+
+        loop {
+            path.push(current);
+            match (&current.left, &current.right) {
+                (None, None) => return Some(path),
+                (left, right) => {
+                    let left_count = Self::leaf_count(left);
+                    if index < left_count {
+                        current = left.as_deref()?;
+                    } else {
+                        index -= left_count;
+                        current = right.as_deref()?;
+                    }
+                }
+            }
+        }
+    }
+
+    fn leaf_count(node: &Option<Box<MerkleNode<M>>>) -> usize {
+        node.as_deref().map_or(0, Self::count_leaves)
+    }
+
+    fn count_leaves(node: &MerkleNode<M>) -> usize {
+        node.leaf_count
+    }
+
+    fn leaf_nodes(&self) -> Vec<&MerkleNode<M>> {
+        let mut leaves = Vec::new();
+        Self::collect_leaves(&self.root, &mut leaves);
+        leaves
+    }
+
+    fn collect_leaves<'a>(
+        node: &'a Option<Box<MerkleNode<M>>>,
+        leaves: &mut Vec<&'a MerkleNode<M>>,
+    ) {
+        if let Some(n) = node {
+            if n.left.is_none() && n.right.is_none() {
+                leaves.push(n);
+            } else {
+                Self::collect_leaves(&n.left, leaves);
+                Self::collect_leaves(&n.right, leaves);
+            }
+        }
+    }
+
+    /// Hashes leaf data exactly as the tree does, so external verifiers can
+    /// recompute the leaf hash a proof starts from.
+    pub fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        H::hash_leaf(data)
+    }
+
+    /// Hashes leaf data bound to its position, as trees built with
+    /// `MerkleTreeBuilder::bind_index` do: the index is prepended to `data`
+    /// as a little-endian `u64` before hashing.
+    pub fn hash_leaf_at(index: usize, data: &[u8]) -> Vec<u8> {
+        let index = (index as u64).to_le_bytes();
+        H::hash_leaf(&[&index[..], data].concat())
+    }
+
+    /// Whether the tree refuses `data` as a leaf under `EmptyLeaves::Reject`.
+    fn rejects(&self, data: &[u8]) -> bool {
+        data.is_empty() && self.empty_leaves == EmptyLeaves::Reject
+    }
+
+    /// The hash of `data` as a leaf at `index` of this tree, after any
+    /// normalization set with `MerkleTreeBuilder::normalize`, or the sentinel
+    /// for empty data under `EmptyLeaves::Sentinel`.
+    fn leaf_data_hash(&self, index: usize, data: &[u8]) -> Vec<u8> {
+        if data.is_empty() && self.empty_leaves == EmptyLeaves::Sentinel {
+            return vec![0; H::hash_len()];
+        }
+        let normalized;
+        let data = match self.normalize {
+            Some(normalize) => {
+                normalized = normalize(data);
+                &normalized[..]
+            }
+            None => data,
+        };
+        if self.bind_index {
+            Self::hash_leaf_at(index, data)
+        } else {
+            Self::hash_leaf(data)
+        }
+    }
+
+    /// Combines two child hashes into their parent hash exactly as the tree
+    /// does.
+    pub fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+        H::hash_nodes(left, right)
+    }
+
+    fn create_new_data_node(data: &[u8], metadata: Option<M>) -> MerkleNode<M> {
+        Self::create_new_hash_node(Self::hash_leaf(data), metadata)
+    }
+
+    fn create_new_hash_node(hash: Vec<u8>, metadata: Option<M>) -> MerkleNode<M> {
+        MerkleNode {
+            left: None,
+            right: None,
+            hash,
+            metadata,
+            leaf_count: 1,
+        }
+    }
+}
+
+// Trees compare, order and hash by their root hash alone, so two trees over
+// the same leaves are equal regardless of metadata, and empty trees order
+// before all others.
+impl<M, H> PartialEq for MerkleTree<M, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.root.as_ref().map(|root| &root.hash) == other.root.as_ref().map(|root| &root.hash)
+    }
+}
+
+impl<M, H> Eq for MerkleTree<M, H> {}
+
+impl<M, H> PartialOrd for MerkleTree<M, H> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<M, H> Ord for MerkleTree<M, H> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let root = self.root.as_ref().map(|root| &root.hash);
+        root.cmp(&other.root.as_ref().map(|root| &root.hash))
+    }
+}
+
+impl<M, H> Hash for MerkleTree<M, H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.root.as_ref().map(|root| &root.hash).hash(state)
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{MerkleHasher, MerkleNode, MerkleTree};
+
+/// Subtrees below this depth are validated serially; above it both children
+/// are validated concurrently, which gives rayon up to `2^PARALLEL_DEPTH`
+/// tasks.
+const PARALLEL_DEPTH: usize = 10;
+
+impl<M: Clone + Send + Sync, H: MerkleHasher> MerkleTree<M, H> {
+    /// Like `validate`, but checks independent subtrees concurrently. It
+    /// reports the same node `validate` would, the first mismatch in
+    /// pre-order.
+    pub fn validate_parallel(&self) -> Result<(), &MerkleNode<M>> {
+        match self.root.as_deref() {
+            Some(root) => Self::validate_node_parallel(root, 0),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_node_parallel(node: &MerkleNode<M>, depth: usize) -> Result<(), &MerkleNode<M>> {
+        if depth >= PARALLEL_DEPTH {
+            return Self::validate_node(node);
+        }
+        match (node.left.as_deref(), node.right.as_deref()) {
+            (None, None) => Ok(()),
+            (Some(left), Some(right)) => {
+                if Self::hash_nodes(&left.hash, &right.hash) != node.hash {
+                    return Err(node);
+                }
+                let (left, right) = rayon::join(
+                    || Self::validate_node_parallel(left, depth + 1),
+                    || Self::validate_node_parallel(right, depth + 1),
+                );
+                left.and(right)
+            }
+            _ => Err(node),
+        }
+    }
+}
@@ -0,0 +1,335 @@
+/// One step of an inclusion proof: the sibling's hash and whether that
+/// sibling sits to the left of the path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofNode {
+    pub hash: Vec<u8>,
+    pub is_left: bool,
+}
+
+/// An inclusion proof, listing siblings from the leaf up to the root. A
+/// single-leaf tree has an empty proof.
+///
+/// The proof also records the leaf's position (`leaf_index` out of
+/// `tree_size` leaves), so a positional verifier can derive every sibling's
+/// side from it instead of trusting `is_left`, binding the leaf to its index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Proof {
+    pub leaf_index: usize,
+    pub tree_size: usize,
+    pub siblings: Vec<ProofNode>,
+}
+
+impl Proof {
+    pub fn len(&self) -> usize {
+        self.siblings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.siblings.is_empty()
+    }
+}
+
+/// Which side each sibling sits on, from the leaf up, for the leaf at
+/// `leaf_index` in the layout `MerkleTree::new` builds over `tree_size`
+/// leaves. Returns `None` if the index is out of range.
+pub(crate) fn midpoint_sibling_sides(leaf_index: usize, tree_size: usize) -> Option<Vec<bool>> {
+    if leaf_index >= tree_size {
+        return None;
+    }
+
+    let (mut index, mut size) = (leaf_index, tree_size);
+    let mut sides = Vec::new();
+    while size > 1 {
+        let mid = size / 2;
+        if index < mid {
+            sides.push(false);
+            size = mid;
+        } else {
+            sides.push(true);
+            index -= mid;
+            size -= mid;
+        }
+    }
+    sides.reverse();
+    Some(sides)
+}
+
+impl Proof {
+    /// Encodes the audit path in the RFC 6962 / RFC 9162 wire layout: a
+    /// big-endian `u16` byte length for the whole path, followed by each node
+    /// hash prefixed with its `u8` length, from the leaf up.
+    ///
+    /// The layout carries no side bits, as CT verifiers derive them from the
+    /// leaf index and tree size. That derivation only matches proofs from
+    /// trees in the append-only layout (see `MerkleTree::append`). The node
+    /// hashes are whatever the tree's hasher produced, so only trees hashed
+    /// with `DomainSeparated<Sha256RawHasher>` give the audit paths a CT log
+    /// serves for the same leaves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node hash is longer than 255 bytes or the whole path
+    /// longer than 65535 bytes, which the wire format cannot represent.
+    pub fn to_ct_bytes(&self) -> Vec<u8> {
+        let mut path = Vec::new();
+        for sibling in &self.siblings {
+            let len = u8::try_from(sibling.hash.len()).expect("node hash longer than 255 bytes");
+            path.push(len);
+            path.extend_from_slice(&sibling.hash);
+        }
+
+        let path_len = u16::try_from(path.len()).expect("audit path longer than 65535 bytes");
+        let mut bytes = path_len.to_be_bytes().to_vec();
+        bytes.extend(path);
+        bytes
+    }
+
+    /// Parses an audit path produced by `to_ct_bytes`, deriving each
+    /// sibling's side from `leaf_index` and `tree_size` as an RFC 9162
+    /// verifier does. Returns `None` if the bytes are malformed or the path
+    /// length does not fit the given position.
+    pub fn from_ct_bytes(bytes: &[u8], leaf_index: usize, tree_size: usize) -> Option<Proof> {
+        let (len, mut path) = bytes.split_at_checked(2)?;
+        if usize::from(u16::from_be_bytes([len[0], len[1]])) != path.len() {
+            return None;
+        }
+
+        let mut hashes = Vec::new();
+        while let Some((&len, rest)) = path.split_first() {
+            let (hash, rest) = rest.split_at_checked(usize::from(len))?;
+            hashes.push(hash.to_vec());
+            path = rest;
+        }
+
+        let sides = ct_sibling_sides(leaf_index, tree_size, hashes.len())?;
+        let siblings = hashes
+            .into_iter()
+            .zip(sides)
+            .map(|(hash, is_left)| ProofNode { hash, is_left })
+            .collect();
+        Some(Proof {
+            leaf_index,
+            tree_size,
+            siblings,
+        })
+    }
+}
+
+/// Which side each sibling of an RFC 9162 inclusion path sits on, for a leaf
+/// at `leaf_index` in a tree of `tree_size` leaves and a path of `path_len`
+/// nodes. Returns `None` if the path length is wrong for that position.
+fn ct_sibling_sides(leaf_index: usize, tree_size: usize, path_len: usize) -> Option<Vec<bool>> {
+    if leaf_index >= tree_size {
+        return None;
+    }
+
+    let (mut f, mut s) = (leaf_index, tree_size - 1);
+    let mut sides = Vec::with_capacity(path_len);
+    for _ in 0..path_len {
+        if s == 0 {
+            return None;
+        }
+        if f & 1 == 1 || f == s {
+            sides.push(true);
+            if f & 1 == 0 {
+                while f & 1 == 0 && f != 0 {
+                    f >>= 1;
+                    s >>= 1;
+                }
+            }
+        } else {
+            sides.push(false);
+        }
+        f >>= 1;
+        s >>= 1;
+    }
+
+    if s != 0 {
+        return None;
+    }
+    Some(sides)
+}
+
+impl Proof {
+    /// Encodes the proof as JSON for web clients, e.g.
+    /// `{"leaf_index":2,"tree_size":4,"siblings":[{"hash":"ab12..","left":true}]}`
+    /// with each sibling hash in lowercase hex, from the leaf up.
+    pub fn to_json(&self) -> String {
+        let siblings = self
+            .siblings
+            .iter()
+            .map(|sibling| {
+                let hash = sibling
+                    .hash
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>();
+                format!(r#"{{"hash":"{hash}","left":{}}}"#, sibling.is_left)
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        format!(
+            r#"{{"leaf_index":{},"tree_size":{},"siblings":[{siblings}]}}"#,
+            self.leaf_index, self.tree_size
+        )
+    }
+
+    /// Parses a proof produced by `to_json`. Keys may come in any order and
+    /// `tree_size` may be omitted, defaulting to 0. Returns `None` for
+    /// malformed input.
+    pub fn from_json(json: &str) -> Option<Proof> {
+        let mut parser = JsonParser {
+            bytes: json.as_bytes(),
+        };
+        let mut proof = Proof::default();
+        let (mut has_index, mut has_siblings) = (false, false);
+        parser.object(|parser, key| {
+            match key {
+                "leaf_index" => {
+                    proof.leaf_index = parser.number()?;
+                    has_index = true;
+                }
+                "tree_size" => proof.tree_size = parser.number()?,
+                "siblings" => {
+                    parser.array(|parser| {
+                        let (mut hash, mut is_left) = (None, None);
+                        parser.object(|parser, key| {
+                            match key {
+                                "hash" => hash = Some(decode_hex(&parser.string()?)?),
+                                "left" => is_left = Some(parser.boolean()?),
+                                _ => return None,
+                            }
+                            Some(())
+                        })?;
+                        proof.siblings.push(ProofNode {
+                            hash: hash?,
+                            is_left: is_left?,
+                        });
+                        Some(())
+                    })?;
+                    has_siblings = true;
+                }
+                _ => return None,
+            }
+            Some(())
+        })?;
+
+        parser.skip_whitespace();
+        (parser.bytes.is_empty() && has_index && has_siblings).then_some(proof)
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Just enough of a JSON reader for `Proof::from_json`: objects, arrays,
+/// escape-free strings, unsigned integers and booleans.
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while let Some((byte, rest)) = self.bytes.split_first() {
+            if !byte.is_ascii_whitespace() {
+                break;
+            }
+            self.bytes = rest;
+        }
+    }
+
+    /// Consumes `byte` if it is the next non-whitespace byte.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        match self.bytes.split_first() {
+            Some((&next, rest)) if next == byte => {
+                self.bytes = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn object(&mut self, mut field: impl FnMut(&mut Self, &str) -> Option<()>) -> Option<()> {
+        if !self.eat(b'{') {
+            return None;
+        }
+        if self.eat(b'}') {
+            return Some(());
+        }
+        loop {
+            let key = self.string()?;
+            if !self.eat(b':') {
+                return None;
+            }
+            field(self, &key)?;
+            if self.eat(b'}') {
+                return Some(());
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn array(&mut self, mut element: impl FnMut(&mut Self) -> Option<()>) -> Option<()> {
+        if !self.eat(b'[') {
+            return None;
+        }
+        if self.eat(b']') {
+            return Some(());
+        }
+        loop {
+            element(self)?;
+            if self.eat(b']') {
+                return Some(());
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if !self.eat(b'"') {
+            return None;
+        }
+        let end = self.bytes.iter().position(|&byte| byte == b'"')?;
+        let (string, rest) = self.bytes.split_at(end);
+        if string.contains(&b'\\') {
+            return None;
+        }
+        self.bytes = &rest[1..];
+        String::from_utf8(string.to_vec()).ok()
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        self.skip_whitespace();
+        let end = self
+            .bytes
+            .iter()
+            .position(|byte| !byte.is_ascii_digit())
+            .unwrap_or(self.bytes.len());
+        let (digits, rest) = self.bytes.split_at(end);
+        self.bytes = rest;
+        std::str::from_utf8(digits).ok()?.parse().ok()
+    }
+
+    fn boolean(&mut self) -> Option<bool> {
+        self.skip_whitespace();
+        for (literal, value) in [(&b"true"[..], true), (&b"false"[..], false)] {
+            if let Some(rest) = self.bytes.strip_prefix(literal) {
+                self.bytes = rest;
+                return Some(value);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{MerkleHasher, MerkleNode, MerkleTree, Proof, ProofNode};
+
+/// Deepest nesting `PrunedTree::from_bytes` accepts, so hostile input cannot
+/// exhaust the stack. Balanced trees stay far below it at any size; only
+/// trees grown deep with `insert` can exceed it.
+const MAX_DECODE_DEPTH: usize = 1024;
+
+/// The part of a tree needed to prove a fixed set of leaves: the nodes on
+/// their paths and the siblings of those nodes. Every other subtree is kept
+/// only as its hash and leaf count. Built by `MerkleTree::prune_to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedTree {
+    root: Option<PrunedNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PrunedNode {
+    /// A subtree none of whose leaves were kept.
+    Opaque { hash: Vec<u8>, leaf_count: usize },
+    /// A kept leaf.
+    Leaf { hash: Vec<u8> },
+    Internal {
+        hash: Vec<u8>,
+        leaf_count: usize,
+        left: Box<PrunedNode>,
+        right: Box<PrunedNode>,
+    },
+}
+
+impl PrunedNode {
+    fn hash(&self) -> &Vec<u8> {
+        match self {
+            PrunedNode::Opaque { hash, .. }
+            | PrunedNode::Leaf { hash }
+            | PrunedNode::Internal { hash, .. } => hash,
+        }
+    }
+
+    fn leaf_count(&self) -> usize {
+        match self {
+            PrunedNode::Leaf { .. } => 1,
+            PrunedNode::Opaque { leaf_count, .. } | PrunedNode::Internal { leaf_count, .. } => {
+                *leaf_count
+            }
+        }
+    }
+}
+
+impl PrunedTree {
+    pub fn root_hash(&self) -> Option<&Vec<u8>> {
+        self.root.as_ref().map(PrunedNode::hash)
+    }
+
+    /// Generates the same proof the full tree would for the leaf at `index`.
+    /// Returns `None` unless `index` is one of the kept leaves.
+    pub fn generate_proof_by_index(&self, index: usize) -> Option<Proof> {
+        let mut node = self.root.as_ref()?;
+        let tree_size = node.leaf_count();
+        let mut offset = index;
+        let mut siblings = Vec::new();
+        loop {
+            match node {
+                PrunedNode::Leaf { .. } if offset == 0 => break,
+                PrunedNode::Internal { left, right, .. } => {
+                    let left_count = left.leaf_count();
+                    if offset < left_count {
+                        siblings.push(ProofNode {
+                            hash: right.hash().clone(),
+                            is_left: false,
+                        });
+                        node = left;
+                    } else {
+                        siblings.push(ProofNode {
+                            hash: left.hash().clone(),
+                            is_left: true,
+                        });
+                        offset -= left_count;
+                        node = right;
+                    }
+                }
+                _ => return None,
+            }
+        }
+
+        siblings.reverse();
+        Some(Proof {
+            leaf_index: index,
+            tree_size,
+            siblings,
+        })
+    }
+
+    /// Encodes the pruned tree in pre-order. Each node starts with a tag
+    /// byte: 0 for an opaque subtree, followed by its big-endian `u32` leaf
+    /// count; 1 for a kept leaf; 2 for an internal node, followed by its two
+    /// children after its hash. Every hash is written as a big-endian `u32`
+    /// length and its bytes. An empty tree encodes as no bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if let Some(root) = &self.root {
+            encode(root, &mut bytes);
+        }
+        bytes
+    }
+
+    /// Parses a pruned tree produced by `to_bytes`. Returns `None` if the
+    /// bytes are malformed or nest more than 1024 levels deep. Hashes are not
+    /// checked against each other.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<PrunedTree> {
+        if bytes.is_empty() {
+            return Some(PrunedTree { root: None });
+        }
+        let root = decode(&mut bytes, 0)?;
+        if !bytes.is_empty() {
+            return None;
+        }
+        Some(PrunedTree { root: Some(root) })
+    }
+}
+
+fn encode(node: &PrunedNode, bytes: &mut Vec<u8>) {
+    match node {
+        PrunedNode::Opaque { hash, leaf_count } => {
+            bytes.push(0);
+            put_u32(bytes, *leaf_count);
+            put_hash(bytes, hash);
+        }
+        PrunedNode::Leaf { hash } => {
+            bytes.push(1);
+            put_hash(bytes, hash);
+        }
+        PrunedNode::Internal {
+            hash, left, right, ..
+        } => {
+            bytes.push(2);
+            put_hash(bytes, hash);
+            encode(left, bytes);
+            encode(right, bytes);
+        }
+    }
+}
+
+fn decode(bytes: &mut &[u8], depth: usize) -> Option<PrunedNode> {
+    if depth > MAX_DECODE_DEPTH {
+        return None;
+    }
+    let (&tag, rest) = bytes.split_first()?;
+    *bytes = rest;
+    match tag {
+        0 => {
+            let leaf_count = take_u32(bytes)?;
+            let hash = take_hash(bytes)?;
+            Some(PrunedNode::Opaque { hash, leaf_count })
+        }
+        1 => Some(PrunedNode::Leaf {
+            hash: take_hash(bytes)?,
+        }),
+        2 => {
+            let hash = take_hash(bytes)?;
+            let left = decode(bytes, depth + 1)?;
+            let right = decode(bytes, depth + 1)?;
+            Some(PrunedNode::Internal {
+                hash,
+                leaf_count: left.leaf_count().checked_add(right.leaf_count())?,
+                left: Box::new(left),
+                right: Box::new(right),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: usize) {
+    let value = u32::try_from(value).expect("pruned tree too large to encode");
+    bytes.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_hash(bytes: &mut Vec<u8>, hash: &[u8]) {
+    put_u32(bytes, hash.len());
+    bytes.extend_from_slice(hash);
+}
+
+fn take_u32(bytes: &mut &[u8]) -> Option<usize> {
+    let (value, rest) = bytes.split_first_chunk::<4>()?;
+    *bytes = rest;
+    usize::try_from(u32::from_be_bytes(*value)).ok()
+}
+
+fn take_hash(bytes: &mut &[u8]) -> Option<Vec<u8>> {
+    let len = take_u32(bytes)?;
+    let (hash, rest) = bytes.split_at_checked(len)?;
+    *bytes = rest;
+    Some(hash.to_vec())
+}
+
+impl<M: Clone, H: MerkleHasher> MerkleTree<M, H> {
+    /// Keeps only what is needed to generate proofs for the leaves at
+    /// `indices`; those proofs verify against this tree's root. Indices out
+    /// of range are ignored.
+    pub fn prune_to(&self, indices: &[usize]) -> PrunedTree {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        PrunedTree {
+            root: self
+                .root
+                .as_deref()
+                .map(|root| Self::prune_node(root, 0, &indices)),
+        }
+    }
+
+    /// Prunes the subtree at `node`, whose first leaf has index `offset`.
+    fn prune_node(node: &MerkleNode<M>, offset: usize, indices: &[usize]) -> PrunedNode {
+        let leaf_count = Self::count_leaves(node);
+        let first = indices.partition_point(|&index| index < offset);
+        if indices
+            .get(first)
+            .is_none_or(|&index| index >= offset + leaf_count)
+        {
+            return PrunedNode::Opaque {
+                hash: node.hash.clone(),
+                leaf_count,
+            };
+        }
+
+        match (node.left.as_deref(), node.right.as_deref()) {
+            (Some(left), Some(right)) => {
+                let left_count = Self::count_leaves(left);
+                PrunedNode::Internal {
+                    hash: node.hash.clone(),
+                    leaf_count,
+                    left: Box::new(Self::prune_node(left, offset, indices)),
+                    right: Box::new(Self::prune_node(right, offset + left_count, indices)),
+                }
+            }
+            _ => PrunedNode::Leaf {
+                hash: node.hash.clone(),
+            },
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use std::collections::HashMap;
+
+/// Looks up leaf data by leaf hash in storage kept outside the tree, for
+/// trees that only hold hashes; see `MerkleTree::generate_proof_with_data`.
+pub trait LeafResolver {
+    fn resolve(&self, hash: &[u8]) -> Option<Vec<u8>>;
+}
+
+impl LeafResolver for HashMap<Vec<u8>, Vec<u8>> {
+    fn resolve(&self, hash: &[u8]) -> Option<Vec<u8>> {
+        self.get(hash).cloned()
+    }
+}
@@ -0,0 +1,51 @@
+/// Leaf hashes sorted for binary search, each with the index of its leaf,
+/// so membership tests take `O(log n)` instead of a full traversal. Enabled
+/// with `MerkleTreeBuilder::sorted_index` or `MerkleTree::enable_sorted_index`
+/// and kept in step with the tree by every mutation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortedLeafIndex {
+    entries: Vec<(Vec<u8>, usize)>,
+}
+
+impl SortedLeafIndex {
+    pub(crate) fn new<'a>(leaf_hashes: impl Iterator<Item = &'a Vec<u8>>) -> Self {
+        let mut entries = leaf_hashes
+            .cloned()
+            .enumerate()
+            .map(|(index, hash)| (hash, index))
+            .collect::<Vec<(Vec<u8>, usize)>>();
+        entries.sort_unstable();
+        SortedLeafIndex { entries }
+    }
+
+    /// Records a leaf added at `index` without moving any other leaf.
+    pub(crate) fn insert(&mut self, leaf_hash: Vec<u8>, index: usize) {
+        let entry = (leaf_hash, index);
+        let position = self.entries.partition_point(|existing| *existing < entry);
+        self.entries.insert(position, entry);
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    /// Index of the first leaf with hash `leaf_hash`.
+    pub fn position(&self, leaf_hash: &[u8]) -> Option<usize> {
+        let first = self
+            .entries
+            .partition_point(|(hash, _)| hash.as_slice() < leaf_hash);
+        match self.entries.get(first) {
+            Some((hash, index)) if hash == leaf_hash => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Number of indexed leaves.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,21 @@
+use std::pin::pin;
+
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{MerkleHasher, MerkleTree};
+
+impl<M: Clone, H: MerkleHasher> MerkleTree<M, H> {
+    /// Builds a tree from leaves as they arrive on `stream`, hashing and
+    /// appending each one with `append` so the leaf data itself is never
+    /// buffered. The tree is in the append-only layout, so its root equals
+    /// `compute_root_streaming` over the same leaves, and `compute_root` when
+    /// the leaf count is a power of two.
+    pub async fn from_async_stream<S: Stream<Item = Vec<u8>>>(stream: S) -> Self {
+        let mut tree = Self::new(&[]);
+        let mut stream = pin!(stream);
+        while let Some(data) = stream.next().await {
+            tree.append(&data);
+        }
+        tree
+    }
+}
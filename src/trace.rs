@@ -0,0 +1,38 @@
+use std::cell::RefCell;
+
+use crate::{MerkleHasher, MerkleTree};
+
+/// One hash operation: the concatenated input handed to the hasher and the
+/// output it produced.
+pub type TraceRecord = (Vec<u8>, Vec<u8>);
+
+thread_local! {
+    static RECORDING: RefCell<Option<Vec<TraceRecord>>> = const { RefCell::new(None) };
+}
+
+/// Adds one call of a hasher's `hash` to the trace that `new_with_trace` is
+/// recording on this thread, if any. The bundled hashers call this from their
+/// `hash`; a custom hasher has to do the same for its calls to be traced.
+pub fn record_hash(datas: &[&[u8]], output: &[u8]) {
+    RECORDING.with(|recording| {
+        if let Some(trace) = recording.borrow_mut().as_mut() {
+            trace.push((datas.concat(), output.to_vec()));
+        }
+    });
+}
+
+impl<M: Clone, H: MerkleHasher> MerkleTree<M, H> {
+    /// Like `new`, but also returns a trace of every hash the build
+    /// performed, recorded as the hasher is called: first each leaf in leaf
+    /// order, then each internal node in post-order. Inputs are the exact
+    /// bytes the hash function saw, including framing such as the
+    /// `DomainSeparated` prefixes, so replaying each input through `H::hash`
+    /// reproduces its output. The order depends only on the leaves, so
+    /// builds over the same data produce byte-identical traces everywhere.
+    pub fn new_with_trace(datas: &[Vec<u8>]) -> (Self, Vec<TraceRecord>) {
+        let outer = RECORDING.with(|recording| recording.replace(Some(Vec::new())));
+        let tree = Self::new(datas);
+        let trace = RECORDING.with(|recording| recording.replace(outer));
+        (tree, trace.unwrap_or_default())
+    }
+}
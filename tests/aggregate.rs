@@ -0,0 +1,48 @@
+use merkle_trees::{AggregatedProof, MerkleTree, Proof};
+
+#[test]
+fn aggregated_proofs_verify_and_are_smaller() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..16u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+    let items = [0, 1, 2, 3, 9]
+        .iter()
+        .map(|&index| {
+            (
+                data[index].clone(),
+                tree.generate_proof_by_index(index).unwrap(),
+            )
+        })
+        .collect::<Vec<(Vec<u8>, Proof)>>();
+    let leaves = items
+        .iter()
+        .map(|(leaf, _)| leaf.clone())
+        .collect::<Vec<_>>();
+
+    let aggregated = T::aggregate_proofs(root, &items).unwrap();
+    assert!(T::verify_aggregated(root, &leaves, &aggregated));
+    let bytes = aggregated.to_bytes();
+    assert_eq!(AggregatedProof::from_bytes(&bytes).unwrap(), aggregated);
+
+    let separate: usize = items
+        .iter()
+        .map(|(_, proof)| proof.to_ct_bytes().len())
+        .sum();
+    assert!(
+        bytes.len() < separate,
+        "aggregated {} bytes, separate {separate} bytes",
+        bytes.len()
+    );
+
+    let mut wrong = leaves.clone();
+    wrong[4] = vec![99];
+    assert!(!T::verify_aggregated(root, &wrong, &aggregated));
+    let mut swapped = leaves.clone();
+    swapped.swap(0, 1);
+    assert!(!T::verify_aggregated(root, &swapped, &aggregated));
+
+    let mut invalid = items.clone();
+    invalid[0].0 = vec![77];
+    assert!(T::aggregate_proofs(root, &invalid).is_none());
+}
@@ -0,0 +1,65 @@
+use merkle_trees::{EmptyLeaves, MerkleTree, MerkleTreeBuilder};
+
+fn numbered(range: std::ops::Range<u32>) -> Vec<Vec<u8>> {
+    range.map(|i| i.to_be_bytes().to_vec()).collect()
+}
+
+#[test]
+fn present_leaves_pass_and_absent_ones_mostly_do_not() {
+    let leaves = numbered(0..200);
+    let tree: MerkleTree = MerkleTree::new(&leaves);
+    let bloom = tree.build_leaf_bloom();
+
+    assert!(leaves.iter().all(|leaf| tree.maybe_contains(&bloom, leaf)));
+    let false_positives = numbered(1000..3000)
+        .iter()
+        .filter(|item| tree.maybe_contains(&bloom, item))
+        .count();
+    assert!(false_positives < 100, "{false_positives} false positives");
+
+    let empty: MerkleTree = MerkleTree::new(&[]);
+    assert!(!empty.maybe_contains(&empty.build_leaf_bloom(), b"x"));
+}
+
+#[test]
+fn bloom_hashes_data_like_contains() {
+    let normalized: MerkleTree = MerkleTreeBuilder::new()
+        .normalize(|data| data.to_ascii_lowercase())
+        .build(&[b"hello".to_vec()]);
+    let bloom = normalized.build_leaf_bloom();
+    assert!(normalized.contains(b"HELLO"));
+    assert!(normalized.maybe_contains(&bloom, b"HELLO"));
+
+    let bound: MerkleTree = MerkleTreeBuilder::new()
+        .bind_index(true)
+        .build(&[b"a".to_vec(), b"b".to_vec()]);
+    let bloom = bound.build_leaf_bloom();
+    assert!(bound.contains(b"b"));
+    assert!(bound.maybe_contains(&bloom, b"b"));
+
+    let sentinel: MerkleTree = MerkleTreeBuilder::new()
+        .empty_leaves(EmptyLeaves::Sentinel)
+        .build(&[b"a".to_vec(), Vec::new()]);
+    let bloom = sentinel.build_leaf_bloom();
+    assert!(sentinel.contains(b""));
+    assert!(sentinel.maybe_contains(&bloom, b""));
+}
+
+#[test]
+fn bloom_over_appended_tree_covers_raw_leaf_hashes() {
+    let mut tree: MerkleTree = MerkleTree::new(&[]);
+    for leaf in numbered(0..300) {
+        tree.append(&leaf);
+    }
+    let bloom = tree.build_leaf_bloom();
+    let present = numbered(0..300)
+        .iter()
+        .all(|leaf| bloom.maybe_contains_hash(&MerkleTree::<()>::hash_leaf(leaf)));
+    assert!(present);
+
+    let short_circuited = numbered(5000..6000)
+        .iter()
+        .filter(|item| !bloom.maybe_contains_hash(&MerkleTree::<()>::hash_leaf(item)))
+        .count();
+    assert!(short_circuited > 950, "only {short_circuited} ruled out");
+}
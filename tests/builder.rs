@@ -0,0 +1,109 @@
+use merkle_trees::{BuildError, EmptyLeaves, MerkleTree, MerkleTreeBuilder};
+
+#[test]
+fn bound_proofs_only_verify_at_their_index() {
+    type T = MerkleTree;
+    let data = vec![b"same".to_vec(); 5];
+    let tree: T = MerkleTreeBuilder::new().bind_index(true).build(&data);
+    let root = tree.root_hash().unwrap();
+    let proof = tree.generate_proof_by_index(2).unwrap();
+    assert!(T::verify_proof_at(root, 2, b"same", &proof));
+    assert!(!T::verify_proof_at(root, 3, b"same", &proof));
+    assert_eq!(T::verify_proof(root, b"same", &proof), Ok(false));
+    assert!(tree.contains(b"same"));
+    assert_eq!(tree.index_of(b"same"), Some(0));
+
+    let mut appended: T = MerkleTreeBuilder::new().bind_index(true).build(&data[..4]);
+    appended.append(b"same");
+    let proof = appended.generate_proof_by_index(4).unwrap();
+    assert!(T::verify_proof_at(
+        appended.root_hash().unwrap(),
+        4,
+        b"same",
+        &proof
+    ));
+
+    let unbound: T = MerkleTreeBuilder::new().build(&data);
+    assert_eq!(unbound, MerkleTree::new(&data));
+}
+
+#[test]
+fn bound_trees_refuse_to_shift_leaves() {
+    let data = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()];
+    let mut tree: MerkleTree = MerkleTreeBuilder::new().bind_index(true).build(&data);
+    let root = tree.root_hash().cloned();
+    assert!(!tree.insert(b"x"));
+    assert!(!tree.insert_hash(MerkleTree::<()>::hash_leaf_at(1, b"x")));
+    assert!(!tree.remove_at(0));
+    assert_eq!(tree.root_hash(), root.as_ref());
+    assert!(data.iter().all(|leaf| tree.contains(leaf)));
+
+    assert!(tree.remove_at(2));
+    tree.append(b"d");
+    assert!([b"a", b"b", b"d"].iter().all(|leaf| tree.contains(*leaf)));
+
+    let mut single: MerkleTree = MerkleTreeBuilder::new().bind_index(true).build(&data[..1]);
+    assert!(single.insert(b"x"));
+    assert!(single.contains(b"a") && single.contains(b"x"));
+}
+
+#[test]
+fn normalization_makes_membership_case_insensitive() {
+    type T = MerkleTree;
+    let tree: T = MerkleTreeBuilder::new()
+        .normalize(|data| data.to_ascii_lowercase())
+        .build(&[b"hello".to_vec(), b"World".to_vec()]);
+    assert!(tree.contains(b"HELLO"));
+    assert!(tree.contains(b"world"));
+    assert!(!tree.contains(b"other"));
+    assert_eq!(
+        tree,
+        MerkleTree::new(&[b"hello".to_vec(), b"world".to_vec()])
+    );
+
+    let proof = tree.prove_and_verify(b"WORLD").unwrap();
+    assert_eq!(
+        T::verify_proof(tree.root_hash().unwrap(), b"world", &proof),
+        Ok(true)
+    );
+
+    let mut grown = tree;
+    grown.append(b"NEW");
+    assert!(grown.contains(b"new"));
+}
+
+#[test]
+fn empty_leaves_follow_the_configured_policy() {
+    let data = vec![b"a".to_vec(), Vec::new(), b"b".to_vec()];
+    let rejected = MerkleTreeBuilder::<()>::new()
+        .empty_leaves(EmptyLeaves::Reject)
+        .try_build(&data);
+    assert_eq!(rejected.err(), Some(BuildError::EmptyLeaf { index: 1 }));
+
+    let sentinel: MerkleTree = MerkleTreeBuilder::new()
+        .empty_leaves(EmptyLeaves::Sentinel)
+        .build(&data);
+    assert_eq!(sentinel.leaf_hashes_packed()[64..128], [0; 64]);
+    assert!(sentinel.contains(b""));
+
+    let hashed: MerkleTree = MerkleTreeBuilder::new().build(&data);
+    assert_eq!(hashed, MerkleTree::new(&data));
+}
+
+#[test]
+fn rejecting_trees_refuse_empty_leaves_later_too() {
+    let data = vec![b"a".to_vec(), b"b".to_vec()];
+    let mut tree: MerkleTree = MerkleTreeBuilder::new()
+        .empty_leaves(EmptyLeaves::Reject)
+        .build(&data);
+    let root = tree.root_hash().cloned();
+    assert!(!tree.append(b""));
+    assert!(!tree.insert(b""));
+    assert!(!tree.insert_with_metadata(b"", ()));
+    assert!(!tree.update(0, b""));
+    assert_eq!((tree.len(), tree.root_hash()), (2, root.as_ref()));
+
+    assert!(tree.append(b"c"));
+    assert!(tree.update(0, b"d"));
+    assert_eq!(tree.len(), 3);
+}
@@ -0,0 +1,136 @@
+use merkle_trees::{
+    DomainSeparated, MerkleHasher, MerkleTree, Proof, Sha256Hasher, Sha256RawHasher,
+};
+
+fn hex(text: &str) -> Vec<u8> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn ct_bytes_round_trip_for_append_only_trees() {
+    for size in 1..20usize {
+        let mut tree: MerkleTree = MerkleTree::new(&[]);
+        for i in 0..size {
+            tree.append(&[i as u8]);
+        }
+        for index in 0..size {
+            let proof = tree.generate_proof_by_index(index).unwrap();
+            let bytes = proof.to_ct_bytes();
+            let (path_len, nodes) = bytes.split_at(2);
+            assert_eq!(
+                usize::from(u16::from_be_bytes([path_len[0], path_len[1]])),
+                nodes.len()
+            );
+            let decoded = Proof::from_ct_bytes(&bytes, index, size).unwrap();
+            assert_eq!(decoded, proof, "size {size}, index {index}");
+        }
+    }
+    assert!(Proof::from_ct_bytes(&[0, 5, 1], 0, 2).is_none());
+}
+
+// The eight-leaf tree of the Certificate Transparency reference
+// implementation's test suite, with its published roots and audit paths.
+#[test]
+fn ct_proofs_match_the_rfc_6962_reference_vectors() {
+    type T = MerkleTree<(), DomainSeparated<Sha256RawHasher>>;
+    let leaves = [
+        "",
+        "00",
+        "10",
+        "2021",
+        "3031",
+        "40414243",
+        "5051525354555657",
+        "606162636465666768696a6b6c6d6e6f",
+    ];
+    let roots = [
+        "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+        "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+        "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+        "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+    ];
+    let mut tree: T = MerkleTree::new(&[]);
+    for leaf in leaves {
+        tree.append(&hex(leaf));
+    }
+    let expected = roots.iter().map(|root| hex(root)).collect::<Vec<_>>();
+    assert_eq!(tree.historical_roots(), &expected[..]);
+
+    let paths = [
+        (
+            0,
+            [
+                "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+                "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+            ],
+        ),
+        (
+            5,
+            [
+                "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+                "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+                "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+            ],
+        ),
+    ];
+    for (index, path) in paths {
+        let mut wire = vec![0, 99];
+        for node in path {
+            wire.push(32);
+            wire.extend(hex(node));
+        }
+        let proof = tree.generate_proof_by_index(index).unwrap();
+        assert_eq!(proof.to_ct_bytes(), wire);
+
+        let parsed = Proof::from_ct_bytes(&wire, index, 8).unwrap();
+        assert_eq!(
+            T::verify_proof(&expected[7], &hex(leaves[index]), &parsed),
+            Ok(true)
+        );
+    }
+}
+
+#[test]
+fn packed_leaf_hashes_round_trip() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let packed = tree.leaf_hashes_packed();
+    assert_eq!(packed.len(), 5 * Sha256Hasher::hash_len());
+
+    let rebuilt = T::from_packed_leaf_hashes(&packed, Sha256Hasher::hash_len()).unwrap();
+    assert_eq!(rebuilt.root_hash(), tree.root_hash());
+    assert!(T::from_packed_leaf_hashes(&packed, 7).is_none());
+}
+
+#[test]
+fn json_proofs_round_trip_and_verify() {
+    type T = MerkleTree;
+    let tree: T = MerkleTree::new(&[b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+    let proof = tree.generate_proof(b"c").unwrap();
+    let json = proof.to_json();
+    assert!(
+        json.starts_with(r#"{"leaf_index":2,"tree_size":3,"siblings":[{"hash":""#),
+        "{json}"
+    );
+
+    let parsed = Proof::from_json(&json).unwrap();
+    assert_eq!(parsed, proof);
+    assert_eq!(
+        T::verify_proof(tree.root_hash().unwrap(), b"c", &parsed),
+        Ok(true)
+    );
+
+    let spaced = r#" { "siblings" : [ ] , "leaf_index" : 0 } "#;
+    assert_eq!(Proof::from_json(spaced).unwrap(), Proof::default());
+    assert!(Proof::from_json(r#"{"leaf_index":0}"#).is_none());
+    assert!(Proof::from_json(&format!("{json}x")).is_none());
+}
@@ -0,0 +1,35 @@
+use merkle_trees::{FixedMerkleTree, MerkleTree};
+
+#[test]
+fn fixed_tree_matches_dynamic_tree() {
+    let data: Vec<Vec<u8>> = (0..16u8).map(|i| vec![i]).collect();
+    let array: [Vec<u8>; 16] = data.clone().try_into().unwrap();
+    let fixed = FixedMerkleTree::<16>::new(&array);
+    let dynamic: MerkleTree = MerkleTree::new(&data);
+    assert_eq!(Some(fixed.root_hash()), dynamic.root_hash());
+    assert_eq!(
+        fixed.leaf_hash(3),
+        Some(&MerkleTree::<()>::hash_leaf(&data[3]))
+    );
+
+    for (index, leaf) in data.iter().enumerate() {
+        let proof = fixed.proof(index).unwrap();
+        assert_eq!(proof.len(), FixedMerkleTree::<16>::DEPTH);
+        assert!(FixedMerkleTree::<16>::verify_proof(
+            fixed.root_hash(),
+            leaf,
+            index,
+            &proof
+        ));
+        assert!(!FixedMerkleTree::<16>::verify_proof(
+            fixed.root_hash(),
+            leaf,
+            (index + 1) % 16,
+            &proof
+        ));
+    }
+    assert!(fixed.proof(16).is_none());
+
+    let single = FixedMerkleTree::<1>::new(&[vec![1]]);
+    assert!(single.proof(0).unwrap().is_empty());
+}
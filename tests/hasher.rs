@@ -0,0 +1,43 @@
+#[cfg(feature = "test-utils")]
+#[test]
+fn fnv1a_trees_build_and_prove() {
+    use merkle_trees::{Fnv1aHasher, MerkleHasher, MerkleTree};
+
+    type T = MerkleTree<(), Fnv1aHasher>;
+    let data: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+    assert_eq!(root.len(), Fnv1aHasher::hash_len());
+    // FNV-1a of the empty input is its offset basis.
+    assert_eq!(
+        Fnv1aHasher::hash(&[]),
+        0xcbf2_9ce4_8422_2325_u64.to_be_bytes()
+    );
+
+    for (index, leaf) in data.iter().enumerate() {
+        let proof = tree.generate_proof_by_index(index).unwrap();
+        assert_eq!(T::verify_proof(root, leaf, &proof), Ok(true));
+    }
+    assert_ne!(Some(root), MerkleTree::<()>::new(&data).root_hash());
+}
+
+#[test]
+fn sha512_proofs_round_trip() {
+    use merkle_trees::{MerkleHasher, MerkleTree, Sha512Hasher};
+
+    type T = MerkleTree<(), Sha512Hasher>;
+    let data: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+    assert_eq!(root.len(), Sha512Hasher::hash_len());
+    assert_eq!(Sha512Hasher::hash_len(), 64);
+
+    for (index, leaf) in data.iter().enumerate() {
+        let proof = tree.generate_proof_by_index(index).unwrap();
+        assert!(proof
+            .siblings
+            .iter()
+            .all(|sibling| sibling.hash.len() == 64));
+        assert_eq!(T::verify_proof(root, leaf, &proof), Ok(true));
+    }
+}
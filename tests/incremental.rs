@@ -0,0 +1,111 @@
+use merkle_trees::{
+    append_to_frontier, compute_root, compute_root_streaming, DomainSeparated,
+    IncrementalMerkleTree, MerkleTree, Sha512Hasher,
+};
+
+fn numbered(count: u8) -> Vec<Vec<u8>> {
+    (0..count).map(|i| vec![i]).collect()
+}
+
+#[test]
+fn frontier_roots_match_compute_root_for_powers_of_two() {
+    let leaves = numbered(16);
+    let mut frontier = Vec::new();
+    for (index, leaf) in leaves.iter().enumerate() {
+        let root = append_to_frontier(&mut frontier, MerkleTree::<()>::hash_leaf(leaf));
+        if (index + 1).is_power_of_two() {
+            assert_eq!(Some(root), compute_root(&leaves[..=index]));
+        }
+    }
+    assert_eq!(frontier.iter().flatten().count(), 1);
+}
+
+#[test]
+fn streaming_root_matches_append_and_compute_root() {
+    for count in 0..40u8 {
+        let leaves = numbered(count);
+        let streamed =
+            compute_root_streaming(leaves.iter().map(|leaf| MerkleTree::<()>::hash_leaf(leaf)));
+
+        let mut appended: MerkleTree = MerkleTree::new(&[]);
+        for leaf in &leaves {
+            appended.append(leaf);
+        }
+        assert_eq!(streamed.as_ref(), appended.root_hash());
+        if count.is_power_of_two() {
+            assert_eq!(streamed, compute_root(&leaves));
+        }
+    }
+    assert_eq!(compute_root_streaming(std::iter::empty()), None);
+}
+
+#[test]
+fn frontier_reloads_and_follows_the_tree_hasher() {
+    type H = DomainSeparated<Sha512Hasher>;
+    let leaves = numbered(13);
+    let mut tree: MerkleTree<(), H> = MerkleTree::new(&[]);
+    for leaf in &leaves {
+        tree.append(leaf);
+    }
+    let full = tree.root_hash().cloned();
+
+    for cut in 0..13 {
+        let mut before = IncrementalMerkleTree::<H>::new();
+        for leaf in &leaves[..cut] {
+            before.append(leaf);
+        }
+        let saved = before.export_frontier();
+        assert!(IncrementalMerkleTree::<H>::from_frontier(saved.clone(), cut + 1).is_none());
+
+        let mut after = IncrementalMerkleTree::<H>::from_frontier(saved, cut).unwrap();
+        let mut root = None;
+        for leaf in &leaves[cut..] {
+            root = Some(after.append(leaf));
+        }
+        assert_eq!(root, full);
+        assert_eq!(after.root(), full);
+        assert_eq!(after.leaf_count(), 13);
+    }
+    assert_eq!(IncrementalMerkleTree::<H>::new().root(), None);
+    assert!(IncrementalMerkleTree::<H>::from_frontier(vec![], 1).is_none());
+
+    let mut default: IncrementalMerkleTree = IncrementalMerkleTree::new();
+    for leaf in &leaves {
+        default.append(leaf);
+    }
+    assert_eq!(
+        default.root(),
+        compute_root_streaming(leaves.iter().map(|leaf| MerkleTree::<()>::hash_leaf(leaf)))
+    );
+}
+
+#[test]
+fn roots_over_appends_lists_every_intermediate_root() {
+    let leaves = numbered(8);
+    let roots = MerkleTree::<()>::roots_over_appends(&leaves);
+    assert_eq!(roots.len(), 8);
+    assert_eq!(roots[0], MerkleTree::<()>::hash_leaf(&leaves[0]));
+    assert_eq!(roots.last().cloned(), compute_root(&leaves));
+    for (count, root) in roots.iter().enumerate() {
+        let streamed = compute_root_streaming(
+            leaves[..=count]
+                .iter()
+                .map(|leaf| MerkleTree::<()>::hash_leaf(leaf)),
+        );
+        assert_eq!(Some(root), streamed.as_ref());
+    }
+    assert!(MerkleTree::<()>::roots_over_appends(&[]).is_empty());
+}
+
+#[test]
+fn presized_frontier_gives_the_same_roots() {
+    let mut plain: IncrementalMerkleTree = IncrementalMerkleTree::new();
+    let mut hinted: IncrementalMerkleTree = IncrementalMerkleTree::with_capacity(5);
+    for i in 0..20u8 {
+        if i == 5 {
+            hinted.reserve(15);
+        }
+        assert_eq!(plain.append(&[i]), hinted.append(&[i]));
+    }
+    assert_eq!(plain, hinted);
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "rayon")]
+
+use merkle_trees::{MerkleNode, MerkleTree};
+
+#[test]
+fn parallel_validation_finds_a_corrupted_node() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..5000u32).map(|i| i.to_le_bytes().to_vec()).collect();
+    let tree: T = MerkleTree::new(&data);
+    assert!(tree.validate_parallel().is_ok());
+
+    let hashes = data[..4]
+        .iter()
+        .map(|leaf| T::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    let intact = MerkleNode::internal(
+        MerkleNode::leaf(hashes[0].clone()),
+        MerkleNode::leaf(hashes[1].clone()),
+        T::hash_nodes(&hashes[0], &hashes[1]),
+    );
+    let corrupted = MerkleNode::internal(
+        MerkleNode::leaf(hashes[2].clone()),
+        MerkleNode::leaf(hashes[3].clone()),
+        vec![7; 64],
+    );
+    let root_hash = T::hash_nodes(intact.hash(), corrupted.hash());
+    let tree: T = MerkleTree::from_root_node(MerkleNode::internal(intact, corrupted, root_hash));
+    assert_eq!(tree.validate_parallel().unwrap_err().hash(), &vec![7; 64]);
+    assert_eq!(tree.validate().unwrap_err().hash(), &vec![7; 64]);
+}
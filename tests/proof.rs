@@ -0,0 +1,297 @@
+use merkle_trees::{DomainSeparated, MerkleNode, MerkleTree, Proof, Sha256Hasher};
+
+fn leaves(items: &[&str]) -> Vec<Vec<u8>> {
+    items.iter().map(|item| item.as_bytes().to_vec()).collect()
+}
+
+#[test]
+fn proofs_from_inconsistent_tree_do_not_panic() {
+    type T = MerkleTree;
+    let a = T::hash_leaf(b"a");
+    let b = T::hash_leaf(b"b");
+    let tree: T = MerkleTree::from_root_node(MerkleNode::internal(
+        MerkleNode::leaf(a),
+        MerkleNode::leaf(b),
+        vec![0; 64],
+    ));
+
+    let proof = tree.generate_proof_by_index(0).unwrap();
+    assert_eq!(
+        T::verify_proof(tree.root_hash().unwrap(), b"a", &proof),
+        Ok(false)
+    );
+    assert!(tree.reissue_proof(b"b").is_some());
+    assert!(tree.preview_update(0, b"c").is_some());
+    let expected = MerkleTree::<()>::new(&leaves(&["a", "b"]));
+    assert_ne!(tree.root_hash(), expected.root_hash());
+}
+
+#[test]
+fn identical_leaves_keep_position_correct_proofs() {
+    type T = MerkleTree<(), DomainSeparated<Sha256Hasher>>;
+    let data = leaves(&["x", "x", "x", "x"]);
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+    for index in 0..4 {
+        let proof = tree.generate_proof_by_index(index).unwrap();
+        assert_eq!(proof.leaf_index, index);
+        assert_eq!(proof.len(), 2);
+        assert_eq!(proof.siblings[0].is_left, index % 2 == 1);
+        assert_eq!(proof.siblings[1].is_left, index >= 2);
+        assert_eq!(T::verify_proof(root, b"x", &proof), Ok(true));
+    }
+
+    // Separated leaf and node hashes keep the children of a node from
+    // passing as leaf data.
+    let hash = T::hash_leaf(b"x");
+    let forged = [&hash[..], &hash[..]].concat();
+    assert!(!tree.contains(&forged));
+    assert_ne!(T::hash_leaf(&forged), T::hash_nodes(&hash, &hash));
+}
+
+#[test]
+fn which_root_picks_the_matching_candidate() {
+    type T = MerkleTree;
+    let trees: Vec<T> = [["a", "b"], ["a", "c"], ["a", "e"]]
+        .iter()
+        .map(|items| MerkleTree::new(&leaves(items)))
+        .collect();
+    let roots: Vec<Vec<u8>> = trees
+        .iter()
+        .map(|tree| tree.root_hash().unwrap().clone())
+        .collect();
+
+    let proof = trees[1].generate_proof(b"c").unwrap();
+    assert_eq!(T::which_root(&T::hash_leaf(b"c"), &proof, &roots), Some(1));
+    assert_eq!(T::which_root(&T::hash_leaf(b"x"), &proof, &roots), None);
+}
+
+#[test]
+fn historical_roots_verify_old_proofs() {
+    type T = MerkleTree;
+    let mut tree: T = MerkleTree::new(&leaves(&["a", "b"]));
+    let proof = tree.generate_proof(b"a").unwrap();
+    tree.append(b"c");
+    tree.append(b"d");
+
+    let history = tree.historical_roots().to_vec();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.last(), tree.root_hash());
+    assert_eq!(T::verify_historical(&history[0], b"a", &proof), Ok(true));
+    assert_eq!(T::verify_historical(&history[2], b"a", &proof), Ok(false));
+}
+
+#[test]
+fn historical_proofs_verify_against_their_version() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..11u8).map(|i| vec![i]).collect();
+    let mut tree: T = MerkleTree::new(&[]);
+    for leaf in &data {
+        tree.append(leaf);
+    }
+    let history = tree.historical_roots().to_vec();
+    let current = tree.root_hash().unwrap();
+
+    for size in 1..=11 {
+        for (index, leaf) in data[..size].iter().enumerate() {
+            let proof = tree.generate_historical_proof(index, size).unwrap();
+            assert_eq!(proof.tree_size, size);
+            assert_eq!(T::verify_proof(&history[size - 1], leaf, &proof), Ok(true));
+            if size < 11 {
+                assert_eq!(T::verify_proof(current, leaf, &proof), Ok(false));
+            }
+        }
+    }
+    assert!(tree.generate_historical_proof(3, 3).is_none());
+    assert!(tree.generate_historical_proof(0, 12).is_none());
+
+    let midpoint: T = MerkleTree::new(&data);
+    assert!(midpoint.generate_historical_proof(0, 5).is_none());
+}
+
+#[test]
+fn shared_tree_serves_proofs_from_many_threads() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let data: Vec<Vec<u8>> = (0..32u8).map(|i| vec![i]).collect();
+    let tree: Arc<MerkleTree> = Arc::new(MerkleTree::new(&data));
+    let workers = (0..4)
+        .map(|worker| {
+            let tree = Arc::clone(&tree);
+            let data = data.clone();
+            thread::spawn(move || {
+                let root = tree.root_hash().unwrap();
+                for index in (worker..data.len()).step_by(4) {
+                    let proof = tree.generate_proof_by_index(index).unwrap();
+                    assert_eq!(
+                        MerkleTree::<()>::verify_proof(root, &data[index], &proof),
+                        Ok(true)
+                    );
+                    assert!(tree.contains(&data[index]));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn refreshed_proofs_follow_updates() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i]).collect();
+    let mut tree: T = MerkleTree::new(&data);
+    let mut proof = tree.generate_proof_by_index(1).unwrap();
+    assert!(tree.update(0, b"new"));
+    assert!(!tree.update(5, b"new"));
+
+    let mut updated = data.clone();
+    updated[0] = b"new".to_vec();
+    assert_eq!(tree, MerkleTree::new(&updated));
+    let root = tree.root_hash().unwrap();
+    assert_eq!(T::verify_proof(root, &data[1], &proof), Ok(false));
+    tree.refresh_proof(&mut proof, 1);
+    assert_eq!(T::verify_proof(root, &data[1], &proof), Ok(true));
+
+    let mut empty = Proof::default();
+    tree.refresh_proof(&mut empty, 4);
+    assert_eq!(T::verify_proof(root, &data[4], &empty), Ok(true));
+}
+
+#[test]
+fn prove_and_verify_returns_verifiable_proofs() {
+    let tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b", "c"]));
+    let proof = tree.prove_and_verify(b"b").unwrap();
+    assert_eq!(
+        MerkleTree::<()>::verify_proof(tree.root_hash().unwrap(), b"b", &proof),
+        Ok(true)
+    );
+    assert!(tree.prove_and_verify(b"q").is_none());
+}
+
+#[test]
+fn proofs_share_root_only_within_one_tree() {
+    type T = MerkleTree;
+    let tree: T = MerkleTree::new(&leaves(&["a", "b", "c"]));
+    let other: T = MerkleTree::new(&leaves(&["a", "b", "x"]));
+    let proof_a = tree.generate_proof(b"a").unwrap();
+    let proof_c = tree.generate_proof(b"c").unwrap();
+    let proof_x = other.generate_proof(b"x").unwrap();
+    assert!(T::proofs_share_root(b"a", &proof_a, b"c", &proof_c));
+    assert!(!T::proofs_share_root(b"a", &proof_a, b"x", &proof_x));
+}
+
+#[test]
+fn single_leaf_trees_have_empty_proofs() {
+    type T = MerkleTree;
+    let tree: T = MerkleTree::new(&leaves(&["solo"]));
+    let root = tree.root_hash().unwrap();
+    assert_eq!(*root, T::hash_leaf(b"solo"));
+
+    let proof = tree.generate_proof(b"solo").unwrap();
+    assert!(proof.is_empty());
+    assert_eq!((proof.leaf_index, proof.tree_size), (0, 1));
+    assert_eq!(tree.generate_proof_by_index(0), Some(proof.clone()));
+    assert_eq!(T::verify_proof(root, b"solo", &proof), Ok(true));
+    assert_eq!(T::verify_proof(root, b"other", &proof), Ok(false));
+    assert!(T::verify_proof_positional(root, b"solo", &proof));
+}
+
+#[test]
+fn reissued_proofs_follow_moved_leaves() {
+    type T = MerkleTree;
+    let mut tree: T = MerkleTree::new(&leaves(&["a", "b", "c", "d"]));
+    let stale = tree.generate_proof(b"c").unwrap();
+    assert!(tree.remove_at(0));
+    let root = tree.root_hash().unwrap();
+    assert_eq!(T::verify_proof(root, b"c", &stale), Ok(false));
+
+    let proof = tree.reissue_proof(b"c").unwrap();
+    assert_eq!(proof.leaf_index, 1);
+    assert_eq!(T::verify_proof(root, b"c", &proof), Ok(true));
+    assert!(tree.reissue_proof(b"a").is_none());
+}
+
+#[test]
+fn proof_len_follows_leaf_depth_in_unbalanced_trees() {
+    let mut tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b"]));
+    tree.insert(b"c");
+    tree.insert(b"d");
+    let lengths = (0..4)
+        .map(|index| tree.proof_len(index).unwrap())
+        .collect::<Vec<usize>>();
+    for (index, length) in lengths.iter().enumerate() {
+        assert_eq!(*length, tree.generate_proof_by_index(index).unwrap().len());
+    }
+    assert_ne!(lengths.iter().min(), lengths.iter().max());
+    assert!(tree.proof_len(4).is_none());
+}
+
+#[test]
+fn proofs_come_with_data_from_a_resolver() {
+    use std::collections::HashMap;
+
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 3]).collect();
+    let hashes = data
+        .iter()
+        .map(|leaf| T::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    let store: HashMap<Vec<u8>, Vec<u8>> = hashes.iter().cloned().zip(data.clone()).collect();
+    let tree: T = MerkleTree::from_leaf_hashes(&hashes);
+
+    let (resolved, proof) = tree.generate_proof_with_data(3, &store).unwrap();
+    assert_eq!(resolved, data[3]);
+    assert_eq!(
+        T::verify_proof(tree.root_hash().unwrap(), &resolved, &proof),
+        Ok(true)
+    );
+
+    let mut lying = store.clone();
+    lying.insert(hashes[1].clone(), vec![9]);
+    assert!(tree.generate_proof_with_data(1, &lying).is_none());
+    assert!(tree.generate_proof_with_data(5, &store).is_none());
+}
+
+#[test]
+fn proofs_follow_appends_to_append_only_trees() {
+    type T = MerkleTree;
+    let mut tree: T = MerkleTree::new(&[]);
+    tree.append(b"first");
+    let mut proof = tree.generate_proof_by_index(0).unwrap();
+    for i in 1..13u8 {
+        tree.append(&[i]);
+        assert!(tree.update_proof_on_append(&mut proof, 0));
+        assert_eq!(
+            T::verify_proof(tree.root_hash().unwrap(), b"first", &proof),
+            Ok(true)
+        );
+        assert_eq!(Some(&proof), tree.generate_proof_by_index(0).as_ref());
+    }
+
+    let mut reshaped: T = MerkleTree::new(&leaves(&["a", "b", "c"]));
+    let mut proof = reshaped.generate_proof_by_index(0).unwrap();
+    let before = proof.clone();
+    reshaped.insert(b"z");
+    assert!(!reshaped.update_proof_on_append(&mut proof, 0));
+    assert_eq!(proof, before);
+    assert!(!tree.update_proof_on_append(&mut proof, 13));
+}
+
+#[test]
+fn perfect_trees_serve_historical_proofs_however_built() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+    let roots = T::roots_over_appends(&data);
+    let mut tree: T = MerkleTree::new(&data);
+    let proof = tree.generate_historical_proof(2, 5).unwrap();
+    assert_eq!(T::verify_proof(&roots[4], &data[2], &proof), Ok(true));
+
+    tree.insert(b"x");
+    assert!(tree.generate_historical_proof(2, 5).is_none());
+    assert!(tree.remove_at(1));
+    assert_eq!(tree, MerkleTree::new(&data));
+    assert!(tree.generate_historical_proof(2, 5).is_some());
+}
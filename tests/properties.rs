@@ -0,0 +1,114 @@
+//! The properties checked by the `fuzz/` targets, run over a small number of
+//! pseudo-random cases so they also hold under a plain `cargo test`.
+
+use merkle_trees::MerkleTree;
+
+const CASES: usize = 64;
+
+/// xorshift64*, seeded per property so failures reproduce.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn bytes(&mut self) -> Vec<u8> {
+        let len = self.below(16);
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+
+    fn leaves(&mut self, min: usize) -> Vec<Vec<u8>> {
+        let len = min + self.below(32);
+        (0..len).map(|_| self.bytes()).collect()
+    }
+}
+
+#[test]
+fn proof_round_trip() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..CASES {
+        let leaves = rng.leaves(1);
+        let tree: MerkleTree = MerkleTree::new(&leaves);
+        let root = tree.root_hash().unwrap();
+        let index = rng.below(leaves.len());
+
+        let proof = tree.generate_proof_by_index(index).unwrap();
+        assert_eq!(
+            MerkleTree::<()>::verify_proof(root, &leaves[index], &proof),
+            Ok(true)
+        );
+
+        let proof = tree.generate_proof(&leaves[index]).unwrap();
+        assert_eq!(
+            MerkleTree::<()>::verify_proof(root, &leaves[index], &proof),
+            Ok(true)
+        );
+    }
+}
+
+#[test]
+fn tampered_proof() {
+    let mut rng = Rng(0xd1b5_4a32_d192_ed03);
+    for _ in 0..CASES {
+        let leaves = rng.leaves(2);
+        let tree: MerkleTree = MerkleTree::new(&leaves);
+        let root = tree.root_hash().unwrap();
+        let index = rng.below(leaves.len());
+
+        let mut proof = tree.generate_proof_by_index(index).unwrap();
+        let position = rng.below(proof.len());
+        let sibling = &mut proof.siblings[position];
+        let bit = rng.below(sibling.hash.len() * 8);
+        sibling.hash[bit / 8] ^= 1 << (bit % 8);
+
+        assert_eq!(
+            MerkleTree::<()>::verify_proof(root, &leaves[index], &proof),
+            Ok(false)
+        );
+    }
+}
+
+#[test]
+fn wrong_leaf() {
+    let mut rng = Rng(0x94d0_49bb_1331_11eb);
+    for _ in 0..CASES {
+        let leaves = rng.leaves(1);
+        let other = rng.bytes();
+        if leaves.contains(&other) {
+            continue;
+        }
+        let tree: MerkleTree = MerkleTree::new(&leaves);
+        let root = tree.root_hash().unwrap();
+        let index = rng.below(leaves.len());
+
+        let proof = tree.generate_proof_by_index(index).unwrap();
+        assert_eq!(
+            MerkleTree::<()>::verify_proof(root, &other, &proof),
+            Ok(false)
+        );
+        assert!(tree.generate_proof(&other).is_none());
+        assert!(!tree.contains(&other));
+    }
+}
+
+#[test]
+fn internal_node_is_not_a_leaf() {
+    let leaves: Vec<Vec<u8>> = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()];
+    let tree: MerkleTree = MerkleTree::new(&leaves);
+    let forged = [
+        MerkleTree::<()>::hash_leaf(b"a"),
+        MerkleTree::<()>::hash_leaf(b"b"),
+    ]
+    .concat();
+
+    assert!(!tree.contains(&forged));
+    assert!(tree.generate_proof(&forged).is_none());
+}
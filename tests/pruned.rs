@@ -0,0 +1,40 @@
+use merkle_trees::{MerkleTree, PrunedTree};
+
+#[test]
+fn pruned_tree_proves_kept_leaves() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..11u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+    let pruned = tree.prune_to(&[2, 7, 40]);
+
+    for index in [2, 7] {
+        let proof = pruned.generate_proof_by_index(index).unwrap();
+        assert_eq!(proof, tree.generate_proof_by_index(index).unwrap());
+        assert_eq!(T::verify_proof(root, &data[index], &proof), Ok(true));
+    }
+    assert!(pruned.generate_proof_by_index(3).is_none());
+    assert!(pruned.generate_proof_by_index(40).is_none());
+
+    let decoded = PrunedTree::from_bytes(&pruned.to_bytes()).unwrap();
+    assert_eq!(decoded, pruned);
+    assert_eq!(decoded.root_hash(), Some(root));
+
+    let single: T = MerkleTree::new(&data[..1]);
+    let proof = single.prune_to(&[0]).generate_proof_by_index(0).unwrap();
+    assert!(proof.is_empty());
+}
+
+#[test]
+fn deeply_nested_bytes_are_rejected() {
+    let internal = [2, 0, 0, 0, 0];
+    let hostile = internal.repeat(200_000);
+    assert!(PrunedTree::from_bytes(&hostile).is_none());
+
+    let mut tree: MerkleTree = MerkleTree::new(&[b"a".to_vec()]);
+    for i in 0..200u8 {
+        tree.insert(&[i]);
+    }
+    let pruned = tree.prune_to(&[0, 1]);
+    assert_eq!(PrunedTree::from_bytes(&pruned.to_bytes()), Some(pruned));
+}
@@ -0,0 +1,50 @@
+use merkle_trees::{MerkleTree, MerkleTreeBuilder};
+
+#[test]
+fn sorted_index_tracks_mutations() {
+    let leaves: Vec<Vec<u8>> = (0..50u8).map(|i| vec![i % 40]).collect();
+    let mut tree: MerkleTree = MerkleTreeBuilder::new().sorted_index(true).build(&leaves);
+    assert_eq!(tree.sorted_index().unwrap().len(), 50);
+    for (index, leaf) in leaves.iter().enumerate() {
+        assert_eq!(tree.index_of(leaf), Some(index % 40));
+        assert!(tree.contains(leaf));
+    }
+    assert!(!tree.contains(&[99]));
+
+    tree.append(&[99]);
+    assert_eq!(tree.index_of(&[99]), Some(50));
+    tree.insert(&[98]);
+    assert_eq!(tree.index_of(&[98]), Some(1));
+    assert_eq!(tree.index_of(&[99]), Some(51));
+    tree.remove_at(0);
+    assert_eq!(tree.index_of(&[98]), Some(0));
+    tree.update(0, &[97]);
+    assert_eq!(tree.index_of(&[97]), Some(0));
+    assert!(!tree.contains(&[98]));
+    tree.truncate(3);
+    assert_eq!(tree.sorted_index().unwrap().len(), 3);
+
+    let mut plain: MerkleTree = MerkleTree::new(&leaves);
+    assert!(plain.sorted_index().is_none());
+    plain.enable_sorted_index();
+    assert_eq!(plain.index_of(&leaves[45]), Some(5));
+}
+
+#[test]
+fn contains_agrees_with_and_without_index() {
+    let leaves: Vec<Vec<u8>> = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()];
+    let plain: MerkleTree = MerkleTree::new(&leaves);
+    let mut indexed: MerkleTree = MerkleTree::new(&leaves);
+    indexed.enable_sorted_index();
+
+    let forged = [
+        MerkleTree::<()>::hash_leaf(b"a"),
+        MerkleTree::<()>::hash_leaf(b"b"),
+    ]
+    .concat();
+    let queries = [b"a".to_vec(), b"d".to_vec(), b"e".to_vec(), forged];
+    for query in &queries {
+        assert_eq!(plain.contains(query), indexed.contains(query));
+        assert_eq!(plain.index_of(query), indexed.index_of(query));
+    }
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "tokio")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use merkle_trees::{compute_root, compute_root_streaming, MerkleTree};
+
+/// Drives a future that never waits on I/O, as an in-memory stream does.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn async_stream_builds_the_streaming_root() {
+    let data: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+    let tree = block_on(MerkleTree::<()>::from_async_stream(tokio_stream::iter(
+        data.clone(),
+    )));
+    assert_eq!(tree.root_hash().cloned(), compute_root(&data));
+
+    let tree = block_on(MerkleTree::<()>::from_async_stream(tokio_stream::iter(
+        data[..5].to_vec(),
+    )));
+    assert_eq!(
+        tree.root_hash().cloned(),
+        compute_root_streaming(
+            data[..5]
+                .iter()
+                .map(|leaf| MerkleTree::<()>::hash_leaf(leaf))
+        )
+    );
+}
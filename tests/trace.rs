@@ -0,0 +1,35 @@
+#![cfg(feature = "trace")]
+
+use merkle_trees::{DomainSeparated, MerkleHasher, MerkleTree, Sha256Hasher};
+
+fn numbered(count: u8) -> Vec<Vec<u8>> {
+    (0..count).map(|i| vec![i]).collect()
+}
+
+#[test]
+fn trace_covers_the_build_and_is_deterministic() {
+    let leaves = numbered(7);
+    let (tree, trace) = MerkleTree::<()>::new_with_trace(&leaves);
+    let (_, hash_count) = MerkleTree::<()>::new_counting_hashes(&leaves);
+    assert_eq!(trace.len(), hash_count);
+    assert_eq!(trace.last().unwrap().1, *tree.root_hash().unwrap());
+
+    let (_, again) = MerkleTree::<()>::new_with_trace(&leaves);
+    assert_eq!(trace, again);
+}
+
+#[test]
+fn trace_replays_through_the_hasher() {
+    type H = DomainSeparated<Sha256Hasher>;
+    let leaves = numbered(5);
+    let (tree, trace) = MerkleTree::<(), H>::new_with_trace(&leaves);
+    assert_eq!(trace.len(), 9);
+    assert_eq!(trace[0].0, [&[0x00][..], &leaves[0]].concat());
+    for (input, output) in &trace {
+        assert_eq!(H::hash(&[input]), *output);
+    }
+    assert_eq!(trace.last().unwrap().1, *tree.root_hash().unwrap());
+
+    let (_, empty) = MerkleTree::<(), H>::new_with_trace(&[]);
+    assert!(empty.is_empty());
+}
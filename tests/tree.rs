@@ -0,0 +1,463 @@
+use merkle_trees::{BuildStrategy, DomainSeparated, MerkleNode, MerkleTree, Sha256Hasher};
+
+fn leaves(items: &[&str]) -> Vec<Vec<u8>> {
+    items.iter().map(|item| item.as_bytes().to_vec()).collect()
+}
+
+fn numbered(count: u8) -> Vec<Vec<u8>> {
+    (0..count).map(|i| vec![i]).collect()
+}
+
+#[test]
+fn metadata_is_kept_per_leaf() {
+    let data = leaves(&["a", "b", "c"]);
+    let tagged = data
+        .iter()
+        .cloned()
+        .zip([1, 2, 3])
+        .collect::<Vec<(Vec<u8>, u32)>>();
+    let mut tree: MerkleTree<u32> = MerkleTree::with_metadata(&tagged);
+    assert_eq!(tree.leaf_metadata(0), Some(&1));
+    assert_eq!(tree.leaf_metadata(2), Some(&3));
+    assert_eq!(tree.leaf_metadata(3), None);
+    assert_eq!(tree, MerkleTree::new(&data));
+
+    tree.insert_with_metadata(b"d", 4);
+    assert_eq!(tree.leaf_metadata(1), Some(&4));
+    tree.insert(b"e");
+    assert_eq!(tree.leaf_metadata(1), None);
+}
+
+#[test]
+fn leaf_paths_detect_a_corrupted_internal_node() {
+    type T = MerkleTree;
+    let data = leaves(&["a", "b", "c", "d", "e"]);
+    let tree: T = MerkleTree::new(&data);
+    assert!((0..5).all(|index| tree.verify_leaf_path(index)));
+    assert!(!tree.verify_leaf_path(5));
+
+    let hashes = data
+        .iter()
+        .map(|leaf| T::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    let corrupted = vec![0; 64];
+    let right_hash = T::hash_nodes(&hashes[2], &hashes[3]);
+    let tree: T = MerkleTree::from_root_node(MerkleNode::internal(
+        MerkleNode::internal(
+            MerkleNode::leaf(hashes[0].clone()),
+            MerkleNode::leaf(hashes[1].clone()),
+            corrupted.clone(),
+        ),
+        MerkleNode::internal(
+            MerkleNode::leaf(hashes[2].clone()),
+            MerkleNode::leaf(hashes[3].clone()),
+            right_hash.clone(),
+        ),
+        T::hash_nodes(&corrupted, &right_hash),
+    ));
+    assert!(!tree.verify_leaf_path(0));
+    assert!(!tree.verify_leaf_path(1));
+    assert!(tree.verify_leaf_path(2));
+}
+
+#[test]
+fn truncate_keeps_append_only_history() {
+    let leaves = numbered(8);
+    let mut rebuilt: MerkleTree = MerkleTree::new(&leaves);
+    rebuilt.truncate(5);
+    assert_eq!(rebuilt, MerkleTree::new(&leaves[..5]));
+
+    let mut appended: MerkleTree = MerkleTree::new(&[]);
+    for leaf in &leaves {
+        appended.append(leaf);
+    }
+    let history = appended.historical_roots().to_vec();
+    appended.truncate(5);
+    assert_eq!(appended.root_hash(), Some(&history[4]));
+    appended.truncate(3);
+    assert_eq!(appended.root_hash(), Some(&history[2]));
+    appended.truncate(0);
+    assert!(appended.root_hash().is_none());
+}
+
+#[test]
+fn appending_to_an_unbalanced_tree_does_not_claim_append_only_layout() {
+    let leaves = numbered(5);
+    let mut perfect: MerkleTree = MerkleTree::new(&leaves[..4]);
+    perfect.append(&leaves[4]);
+    let history = perfect.historical_roots().to_vec();
+    perfect.truncate(4);
+    assert_eq!(perfect.root_hash(), Some(&history[0]));
+
+    let mut unbalanced: MerkleTree = MerkleTree::new(&leaves[..3]);
+    unbalanced.append(&leaves[3]);
+    unbalanced.append(&leaves[4]);
+    unbalanced.truncate(4);
+    assert_eq!(unbalanced, MerkleTree::new(&leaves[..4]));
+
+    let mut unbalanced: MerkleTree = MerkleTree::new(&leaves[..3]);
+    unbalanced.append(&leaves[3]);
+    let mut proof = unbalanced.generate_proof_by_index(0).unwrap();
+    let stale = proof.clone();
+    unbalanced.append(&leaves[4]);
+    assert!(!unbalanced.update_proof_on_append(&mut proof, 0));
+    assert_eq!(proof, stale);
+}
+
+#[test]
+fn first_mismatch_reports_the_first_divergent_leaf() {
+    type T = MerkleTree;
+    let leaves = numbered(11);
+    let tree: T = MerkleTree::new(&leaves);
+    let mut expected: Vec<Vec<u8>> = leaves.iter().map(|leaf| T::hash_leaf(leaf)).collect();
+    assert_eq!(tree.first_mismatch(&expected), None);
+
+    expected[7] = T::hash_leaf(b"x");
+    expected[9] = T::hash_leaf(b"y");
+    assert_eq!(tree.first_mismatch(&expected), Some(7));
+    assert_eq!(tree.first_mismatch(&expected[..5]), Some(5));
+    assert_eq!(tree.first_mismatch(&expected[..8]), Some(7));
+
+    let empty: T = MerkleTree::new(&[]);
+    assert_eq!(empty.first_mismatch(&[]), None);
+    assert_eq!(empty.first_mismatch(&expected), Some(0));
+}
+
+#[test]
+fn remove_at_rebuilds_over_the_remaining_leaves() {
+    let data = leaves(&["a", "b", "c", "d"]);
+    let mut tree: MerkleTree = MerkleTree::new(&data);
+    assert!(tree.remove_at(0));
+    assert_eq!(tree, MerkleTree::new(&data[1..]));
+    assert!(!tree.remove_at(3));
+    assert_eq!(tree.len(), 3);
+}
+
+#[test]
+fn first_and_last_leaf_hashes() {
+    type T = MerkleTree;
+    let data = leaves(&["a", "b", "c", "d", "e"]);
+    let tree: T = MerkleTree::new(&data);
+    assert_eq!(tree.first_leaf_hash(), Some(&T::hash_leaf(b"a")));
+    assert_eq!(tree.last_leaf_hash(), Some(&T::hash_leaf(b"e")));
+
+    let empty: T = MerkleTree::new(&[]);
+    assert_eq!(empty.first_leaf_hash(), None);
+    assert_eq!(empty.last_leaf_hash(), None);
+}
+
+#[test]
+fn leaves_with_prefix_finds_matching_leaves() {
+    let tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b", "c", "d"]));
+    let hash = MerkleTree::<()>::hash_leaf(b"c");
+    assert_eq!(tree.leaves_with_prefix(&hash[..4]), vec![2]);
+    assert_eq!(tree.leaves_with_prefix(&[]), vec![0, 1, 2, 3]);
+    assert!(tree.leaves_with_prefix(b"zz").is_empty());
+}
+
+#[test]
+fn hash_leaf_matches_the_built_leaves() {
+    type T = MerkleTree;
+    let tree: T = MerkleTree::new(&leaves(&["hello", "b"]));
+    assert_eq!(tree.first_leaf_hash(), Some(&T::hash_leaf(b"hello")));
+    assert_eq!(
+        tree.root_hash(),
+        Some(&T::hash_nodes(&T::hash_leaf(b"hello"), &T::hash_leaf(b"b")))
+    );
+}
+
+#[test]
+fn insert_hash_matches_insert() {
+    type T = MerkleTree;
+    let data = leaves(&["a", "b", "c"]);
+    let mut by_data: T = MerkleTree::new(&data);
+    let hashes = data
+        .iter()
+        .map(|leaf| T::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    let mut by_hash: T = MerkleTree::from_leaf_hashes(&hashes);
+    assert_eq!(by_data, by_hash);
+
+    by_data.insert(b"x");
+    by_hash.insert_hash(T::hash_leaf(b"x"));
+    assert_eq!(by_data, by_hash);
+    assert!(by_hash.contains(b"x"));
+}
+
+#[test]
+fn trees_are_keyed_on_their_root() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let a: MerkleTree = MerkleTree::new(&leaves(&["a"]));
+    let b: MerkleTree = MerkleTree::new(&leaves(&["b"]));
+    let mut set = HashSet::new();
+    set.insert(a);
+    set.insert(b);
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&MerkleTree::new(&leaves(&["a"]))));
+
+    let mut ordered = BTreeSet::new();
+    ordered.insert(MerkleTree::<()>::new(&leaves(&["a"])));
+    ordered.insert(MerkleTree::new(&[]));
+    assert!(ordered.first().unwrap().root_hash().is_none());
+}
+
+#[test]
+fn from_root_node_wraps_nodes_as_given() {
+    type T = MerkleTree;
+    let (a, b) = (T::hash_leaf(b"a"), T::hash_leaf(b"b"));
+    let tree: T = MerkleTree::from_root_node(MerkleNode::internal(
+        MerkleNode::leaf(a.clone()),
+        MerkleNode::leaf(b.clone()),
+        T::hash_nodes(&a, &b),
+    ));
+    assert_eq!(tree, MerkleTree::new(&leaves(&["a", "b"])));
+    assert!(tree.validate().is_ok());
+
+    let inconsistent: T = MerkleTree::from_root_node(MerkleNode::internal(
+        MerkleNode::leaf(a),
+        MerkleNode::leaf(b),
+        vec![1],
+    ));
+    assert_eq!(inconsistent.root_hash(), Some(&vec![1]));
+    assert!(inconsistent.validate().is_err());
+}
+
+#[test]
+fn lca_hash_finds_the_common_ancestor() {
+    let tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b", "c", "d"]));
+    let left: MerkleTree = MerkleTree::new(&leaves(&["a", "b"]));
+    assert_eq!(tree.lca_hash(0, 1).as_ref(), left.root_hash());
+    assert_eq!(tree.lca_hash(1, 2).as_ref(), tree.root_hash());
+    assert_eq!(tree.lca_hash(3, 3), Some(MerkleTree::<()>::hash_leaf(b"d")));
+    assert_eq!(tree.lca_hash(0, 4), None);
+}
+
+#[test]
+fn builds_hash_every_node_once() {
+    let data = numbered(4);
+    let (tree, hash_count) = MerkleTree::<()>::new_counting_hashes(&data);
+    assert_eq!(hash_count, 7);
+    assert_eq!(tree, MerkleTree::new(&data));
+    assert_eq!(MerkleTree::<()>::new_counting_hashes(&numbered(5)).1, 9);
+    assert_eq!(MerkleTree::<()>::new_counting_hashes(&[]).1, 0);
+
+    type Separated = MerkleTree<(), DomainSeparated<Sha256Hasher>>;
+    let (separated, hash_count) = Separated::new_counting_hashes(&data);
+    assert_eq!(hash_count, 7);
+    assert_eq!(separated, MerkleTree::new(&data));
+}
+
+#[test]
+fn append_subtree_links_balanced_subtrees() {
+    type T = MerkleTree;
+    let data = numbered(8);
+    let hashes = data[4..]
+        .iter()
+        .map(|leaf| T::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    let pair = |i: usize| {
+        MerkleNode::internal(
+            MerkleNode::leaf(hashes[i].clone()),
+            MerkleNode::leaf(hashes[i + 1].clone()),
+            T::hash_nodes(&hashes[i], &hashes[i + 1]),
+        )
+    };
+    let subtree = MerkleNode::internal(
+        pair(0),
+        pair(2),
+        MerkleTree::<()>::new(&data[4..])
+            .root_hash()
+            .unwrap()
+            .clone(),
+    );
+
+    let mut balanced: T = MerkleTree::new(&data[..4]);
+    balanced.append_subtree(subtree.clone());
+    assert_eq!(balanced, MerkleTree::new(&data));
+
+    let mut uneven: T = MerkleTree::new(&data[..3]);
+    uneven.append_subtree(subtree.clone());
+    let combined = [&data[..3], &data[4..]].concat();
+    assert_eq!(uneven, MerkleTree::new(&combined));
+
+    let mut reshaped: T = MerkleTree::new(&data[..2]);
+    reshaped.insert(&data[2]);
+    reshaped.insert(&data[3]);
+    assert!(!reshaped.is_perfect());
+    reshaped.append_subtree(subtree);
+    let combined = [0, 3, 2, 1, 4, 5, 6, 7].map(|i| data[i].clone());
+    assert_eq!(reshaped, MerkleTree::new(&combined));
+    assert!(reshaped.is_perfect());
+}
+
+#[test]
+fn bottom_up_pairs_adjacent_nodes() {
+    type T = MerkleTree;
+    let data = numbered(4);
+    let tree: T = MerkleTree::new_with_strategy(&data, BuildStrategy::BottomUp);
+    let leaf = |i: usize| T::hash_leaf(&data[i]);
+    let expected = T::hash_nodes(
+        &T::hash_nodes(&leaf(0), &leaf(1)),
+        &T::hash_nodes(&leaf(2), &leaf(3)),
+    );
+    assert_eq!(tree.root_hash(), Some(&expected));
+
+    let data = numbered(5);
+    let bottom_up: T = MerkleTree::new_with_strategy(&data, BuildStrategy::BottomUp);
+    let mut appended: T = MerkleTree::new(&[]);
+    for leaf in &data {
+        appended.append(leaf);
+    }
+    assert_eq!(bottom_up, appended);
+    assert_ne!(bottom_up, MerkleTree::new(&data));
+    assert_eq!(
+        MerkleTree::<()>::new_with_strategy(&data, BuildStrategy::TopDown),
+        MerkleTree::new(&data)
+    );
+}
+
+#[test]
+fn distinct_leaf_count_ignores_repeats() {
+    let tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b", "a", "c"]));
+    assert_eq!(tree.len(), 4);
+    assert_eq!(tree.distinct_leaf_count(), tree.len() - 1);
+
+    let empty: MerkleTree = MerkleTree::new(&[]);
+    assert!(empty.is_empty());
+    assert_eq!(empty.distinct_leaf_count(), 0);
+}
+
+#[test]
+fn depth_of_counts_from_the_root() {
+    let tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b", "c", "d"]));
+    assert_eq!(tree.depth_of(&MerkleTree::<()>::hash_leaf(b"c")), Some(2));
+    assert_eq!(tree.depth_of(tree.root_hash().unwrap()), Some(0));
+    assert_eq!(tree.depth_of(b"zz"), None);
+}
+
+#[test]
+fn is_perfect_requires_a_full_balanced_tree() {
+    assert!(MerkleTree::<()>::new(&numbered(4)).is_perfect());
+    assert!(!MerkleTree::<()>::new(&numbered(3)).is_perfect());
+    assert!(MerkleTree::<()>::new(&numbered(1)).is_perfect());
+    assert!(!MerkleTree::<()>::new(&[]).is_perfect());
+
+    let mut reshaped: MerkleTree = MerkleTree::new(&numbered(2));
+    reshaped.insert(b"c");
+    reshaped.insert(b"d");
+    assert!(!reshaped.is_perfect());
+}
+
+#[test]
+fn preview_update_matches_the_real_update() {
+    let mut tree: MerkleTree = MerkleTree::new(&leaves(&["a", "b", "c"]));
+    tree.insert(b"e");
+    let before = tree.root_hash().cloned();
+    let preview = tree.preview_update(2, b"zz").unwrap();
+    assert_eq!(tree.root_hash().cloned(), before);
+
+    assert!(tree.update(2, b"zz"));
+    assert_eq!(tree.root_hash(), Some(&preview));
+    assert!(tree.preview_update(9, b"x").is_none());
+}
+
+#[test]
+fn subset_root_over_all_indices_is_the_root() {
+    let data = numbered(7);
+    let tree: MerkleTree = MerkleTree::new(&data);
+    let all = (0..7).collect::<Vec<usize>>();
+    assert_eq!(tree.subset_root(&all).as_ref(), tree.root_hash());
+    assert_eq!(
+        tree.subset_root(&[1, 4]),
+        merkle_trees::compute_root(&[data[1].clone(), data[4].clone()])
+    );
+    assert!(tree.subset_root(&[1, 7]).is_none());
+}
+
+#[test]
+fn iter_bfs_starts_at_the_root_and_ends_with_leaves() {
+    let data = leaves(&["a", "b", "c", "d"]);
+    let tree: MerkleTree = MerkleTree::new(&data);
+    let nodes = tree.iter_bfs().collect::<Vec<_>>();
+    assert_eq!(nodes.len(), 7);
+    assert_eq!(nodes[0].hash(), tree.root_hash().unwrap());
+    let leaf_hashes = nodes[3..]
+        .iter()
+        .map(|node| node.hash().clone())
+        .collect::<Vec<_>>();
+    let expected = data
+        .iter()
+        .map(|leaf| MerkleTree::<()>::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    assert_eq!(leaf_hashes, expected);
+    assert_eq!(MerkleTree::<()>::new(&[]).iter_bfs().count(), 0);
+}
+
+#[test]
+fn from_leaves_matches_new_when_hashing_agrees() {
+    let data = numbered(5);
+    let nodes = data
+        .iter()
+        .map(|leaf| MerkleNode::leaf(MerkleTree::<()>::hash_leaf(leaf)))
+        .collect();
+    let tree: MerkleTree = MerkleTree::from_leaves(nodes);
+    assert_eq!(tree, MerkleTree::new(&data));
+    assert!(tree.validate().is_ok());
+}
+
+#[test]
+fn odd_leaf_policies_only_differ_on_odd_levels() {
+    use merkle_trees::OddLeafPolicy::{Duplicate, Midpoint, Promote};
+
+    let data = numbered(8);
+    let perfect: MerkleTree = MerkleTree::new(&data);
+    for policy in [Midpoint, Promote, Duplicate] {
+        assert_eq!(
+            perfect.root_under_policy(policy).as_ref(),
+            perfect.root_hash()
+        );
+    }
+
+    let odd: MerkleTree = MerkleTree::new(&data[..5]);
+    let roots = [Midpoint, Promote, Duplicate]
+        .iter()
+        .map(|&policy| odd.root_under_policy(policy).unwrap())
+        .collect::<Vec<Vec<u8>>>();
+    assert_eq!(Some(&roots[0]), odd.root_hash());
+    assert_ne!(roots[0], roots[1]);
+    assert_ne!(roots[1], roots[2]);
+    assert_ne!(roots[0], roots[2]);
+    let bottom_up: MerkleTree = MerkleTree::new_with_strategy(&data[..5], BuildStrategy::BottomUp);
+    assert_eq!(Some(&roots[1]), bottom_up.root_hash());
+
+    let empty: MerkleTree = MerkleTree::new(&[]);
+    assert!(empty.root_under_policy(Duplicate).is_none());
+}
+
+#[test]
+fn commit_set_ignores_order_and_repeats() {
+    let set: MerkleTree = MerkleTree::commit_set(&leaves(&["x", "y", "z"]));
+    let shuffled: MerkleTree = MerkleTree::commit_set(&leaves(&["z", "x", "y", "x", "z"]));
+    assert_eq!(set, shuffled);
+    assert_eq!(shuffled.len(), 3);
+    assert_ne!(set, MerkleTree::commit_set(&leaves(&["x", "y"])));
+}
+
+#[test]
+fn capacity_hints_do_not_change_roots() {
+    let data = numbered(37);
+    let mut plain: MerkleTree = MerkleTree::new(&[]);
+    let mut hinted: MerkleTree = MerkleTree::with_capacity(10);
+    hinted.enable_sorted_index();
+    for (index, leaf) in data.iter().enumerate() {
+        if index == 10 {
+            hinted.reserve(27);
+        }
+        plain.append(leaf);
+        hinted.append(leaf);
+    }
+    assert_eq!(plain, hinted);
+    assert_eq!(plain.historical_roots(), hinted.historical_roots());
+    assert_eq!(hinted.index_of(&data[30]), Some(30));
+}
@@ -0,0 +1,245 @@
+use merkle_trees::{
+    compute_root, DomainSeparated, MerkleTree, Proof, ProofNode, Sha256Hasher, Sha512Hasher,
+    VerifyError,
+};
+
+fn leaves(items: &[&str]) -> Vec<Vec<u8>> {
+    items.iter().map(|item| item.as_bytes().to_vec()).collect()
+}
+
+#[test]
+fn mismatched_hash_lengths_are_errors() {
+    type T = MerkleTree<(), Sha512Hasher>;
+    let tree: T = MerkleTree::new(&leaves(&["a", "b"]));
+    let root = tree.root_hash().unwrap();
+    let proof = tree.generate_proof(b"a").unwrap();
+    assert_eq!(T::verify_proof(root, b"a", &proof), Ok(true));
+    assert_eq!(T::verify_proof(root, b"b", &proof), Ok(false));
+
+    let short_root = vec![1; 32];
+    let root_error = VerifyError::HashLengthMismatch {
+        position: None,
+        expected: 64,
+        found: 32,
+    };
+    assert_eq!(
+        T::verify_proof(&short_root, b"a", &proof),
+        Err(root_error.clone())
+    );
+    assert_eq!(
+        T::verify_proof_detailed(&short_root, b"a", &proof),
+        Err(root_error)
+    );
+
+    let mut short_sibling = proof.clone();
+    short_sibling.siblings[0].hash.truncate(32);
+    assert_eq!(
+        T::verify_proof(root, b"a", &short_sibling),
+        Err(VerifyError::HashLengthMismatch {
+            position: Some(0),
+            expected: 64,
+            found: 32,
+        })
+    );
+}
+
+#[test]
+fn verify_root_checks_a_claimed_root() {
+    type T = MerkleTree<(), DomainSeparated<Sha256Hasher>>;
+    let data = leaves(&["a", "b", "c"]);
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+    assert!(T::verify_root(&data, root));
+    assert!(!T::verify_root(&leaves(&["a", "x", "c"]), root));
+    assert!(!MerkleTree::<()>::verify_root(&data, root));
+    assert!(!T::verify_root(&[], root));
+}
+
+#[test]
+fn verify_proof_detailed_names_each_failure() {
+    type T = MerkleTree;
+    let tree: T = MerkleTree::new(&leaves(&["a", "b", "c", "d"]));
+    let root = tree.root_hash().unwrap();
+    let proof = tree.generate_proof(b"a").unwrap();
+    assert_eq!(T::verify_proof_detailed(root, b"a", &proof), Ok(()));
+
+    assert_eq!(
+        T::verify_proof_detailed(root, b"z", &proof),
+        Err(VerifyError::RootMismatch)
+    );
+    assert_eq!(
+        T::verify_proof_detailed(root, b"a", &Proof::default()),
+        Err(VerifyError::EmptyProof)
+    );
+    let mut long_sibling = proof.clone();
+    long_sibling.siblings[1].hash.push(0);
+    assert_eq!(
+        T::verify_proof_detailed(root, b"a", &long_sibling),
+        Err(VerifyError::HashLengthMismatch {
+            position: Some(1),
+            expected: 64,
+            found: 65,
+        })
+    );
+    assert_eq!(
+        T::verify_proof_detailed(&root[..32], b"a", &proof),
+        Err(VerifyError::HashLengthMismatch {
+            position: None,
+            expected: 64,
+            found: 32,
+        })
+    );
+
+    let error = T::verify_proof_detailed(root, b"a", &long_sibling).unwrap_err();
+    assert_eq!(error.to_string(), "sibling 1 is 65 bytes long, expected 64");
+}
+
+#[test]
+fn positional_proofs_bind_the_leaf_index() {
+    type T = MerkleTree;
+    for size in 1..12u8 {
+        let data: Vec<Vec<u8>> = (0..size).map(|i| vec![i]).collect();
+        let tree: T = MerkleTree::new(&data);
+        let root = tree.root_hash().unwrap();
+        for (index, leaf) in data.iter().enumerate() {
+            let mut proof = tree.generate_proof_by_index(index).unwrap();
+            assert_eq!((proof.leaf_index, proof.tree_size), (index, data.len()));
+            // The side bits are ignored in favour of the position.
+            for sibling in &mut proof.siblings {
+                sibling.is_left = !sibling.is_left;
+            }
+            assert!(T::verify_proof_positional(root, leaf, &proof));
+            if size > 1 {
+                proof.leaf_index = (index + 1) % data.len();
+                assert!(!T::verify_proof_positional(root, leaf, &proof));
+            }
+        }
+    }
+}
+
+#[test]
+fn validated_verification_flags_leaf_sibling_forgeries() {
+    type T = MerkleTree;
+    let leaf = T::hash_leaf(b"x");
+    let root = T::hash_nodes(&leaf, &leaf);
+    let forged = Proof {
+        leaf_index: 0,
+        tree_size: 2,
+        siblings: vec![ProofNode {
+            hash: leaf,
+            is_left: false,
+        }],
+    };
+    assert_eq!(T::verify_proof(&root, b"x", &forged), Ok(true));
+    assert_eq!(
+        T::verify_proof_validated(&root, b"x", &forged, true),
+        Ok(false)
+    );
+    assert_eq!(
+        T::verify_proof_validated(&root, b"x", &forged, false),
+        Ok(true)
+    );
+
+    let tree: T = MerkleTree::new(&leaves(&["a", "b"]));
+    let proof = tree.generate_proof(b"a").unwrap();
+    assert_eq!(
+        T::verify_proof_validated(tree.root_hash().unwrap(), b"a", &proof, true),
+        Ok(true)
+    );
+}
+
+#[test]
+fn auto_verification_accepts_sorted_and_positional_proofs() {
+    type T = MerkleTree;
+    let data = leaves(&["a", "b", "c", "d"]);
+    let tree: T = MerkleTree::new(&data);
+    let positional = tree.generate_proof(b"c").unwrap();
+    assert_eq!(
+        T::verify_proof_auto(tree.root_hash().unwrap(), b"c", &positional),
+        Ok(true)
+    );
+
+    let hashes = data
+        .iter()
+        .map(|leaf| T::hash_leaf(leaf))
+        .collect::<Vec<_>>();
+    let sorted_pair = |a: &Vec<u8>, b: &Vec<u8>| {
+        if a < b {
+            T::hash_nodes(a, b)
+        } else {
+            T::hash_nodes(b, a)
+        }
+    };
+    let left = sorted_pair(&hashes[0], &hashes[1]);
+    let root = sorted_pair(&left, &sorted_pair(&hashes[2], &hashes[3]));
+    let sorted = Proof {
+        leaf_index: 0,
+        tree_size: 0,
+        siblings: vec![
+            ProofNode {
+                hash: hashes[3].clone(),
+                is_left: false,
+            },
+            ProofNode {
+                hash: left,
+                is_left: false,
+            },
+        ],
+    };
+    assert!(T::verify_proof_sorted(&root, b"c", &sorted));
+    assert_eq!(T::verify_proof_auto(&root, b"c", &sorted), Ok(true));
+    assert_eq!(T::verify_proof_auto(&root, b"a", &sorted), Ok(false));
+}
+
+#[test]
+fn range_digests_verify_against_the_root() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let root = tree.root_hash().unwrap();
+
+    let (digest, proof) = tree.range_digest(2, 6).unwrap();
+    assert_eq!(Some(digest.clone()), compute_root(&data[2..6]));
+    assert_eq!(proof.len(), 2);
+    assert!(proof.siblings[0].is_left && !proof.siblings[1].is_left);
+    assert!(T::verify_range_digest(root, &data[2..6], &digest, &proof));
+    let mut tampered = data[2..6].to_vec();
+    tampered[1] = vec![42];
+    assert!(!T::verify_range_digest(root, &tampered, &digest, &proof));
+    let shorter = compute_root(&data[2..5]).unwrap();
+    assert!(!T::verify_range_digest(root, &data[2..5], &shorter, &proof));
+
+    for count in 1..12u8 {
+        let data: Vec<Vec<u8>> = (0..count).map(|i| vec![i]).collect();
+        let tree: T = MerkleTree::new(&data);
+        let root = tree.root_hash().unwrap();
+        let count = usize::from(count);
+        for start in 0..count {
+            for end in start + 1..=count {
+                let (digest, proof) = tree.range_digest(start, end).unwrap();
+                assert!(
+                    T::verify_range_digest(root, &data[start..end], &digest, &proof),
+                    "{start}..{end} of {count}"
+                );
+            }
+        }
+        assert!(tree.range_digest(0, count + 1).is_none());
+    }
+
+    let mut reshaped: T = MerkleTree::new(&data[..2]);
+    reshaped.insert(b"x");
+    reshaped.insert(b"y");
+    assert!(reshaped.range_digest(0, 2).is_none());
+}
+
+#[test]
+fn range_proofs_with_huge_indices_are_rejected() {
+    type T = MerkleTree;
+    let data: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i]).collect();
+    let tree: T = MerkleTree::new(&data);
+    let (digest, mut proof) = tree.range_digest(1, 3).unwrap();
+    proof.leaf_index = usize::MAX;
+    proof.tree_size = usize::MAX;
+    let root = tree.root_hash().unwrap();
+    assert!(!T::verify_range_digest(root, &data[1..3], &digest, &proof));
+}